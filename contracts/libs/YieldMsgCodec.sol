@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity ^0.8.22;
+
+library YieldMsgCodec {
+    uint32 public constant PROTOCOL_VERSION = 1;
+
+    /// @notice Id of a yield message, matching `CrossChainMessage::generate_message_id` on Solana.
+    /// @dev Covers the header fields as they go on the wire and the ABI encoded payload, leaving out
+    /// the id itself and the lengths.
+    function messageId(
+        uint8 _msgType,
+        uint32 _version,
+        uint64 _nonce,
+        int64 _timestamp,
+        bytes memory _payload
+    ) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(_msgType, _version, _nonce, _timestamp, _payload));
+    }
+}
@@ -0,0 +1,883 @@
+use crate::msg_codec::{
+    EmergencyActionType, FeeType, MsgCodecError, ProtocolConfigType, YieldMessage,
+    DEPOSIT_REQUEST_TYPE, EMERGENCY_ACTION_TYPE, FEE_COLLECTION_TYPE, GOVERNANCE_VOTE_TYPE,
+    LIQUIDATION_NOTICE_TYPE, MESSAGE_ACK_TYPE, POSITION_SYNC_TYPE, PRICE_UPDATE_TYPE,
+    PROTOCOL_CONFIG_TYPE, REBALANCE_REQUEST_TYPE, WITHDRAWAL_RELEASE_TYPE, WITHDRAW_REQUEST_TYPE,
+    YIELD_DISTRIBUTION_TYPE, YIELD_UPDATE_TYPE,
+};
+use anchor_lang::prelude::*;
+
+// -----------------------------------------------------------------------------
+// EVM ABI encoding of YieldMessage payloads
+// Each variant is encoded exactly like Solidity's `abi.encode` of its fields in declaration
+// order, so an EVM contract reads it with `abi.decode(payload, (...))`. The variant itself is
+// identified by `msg_type` in the message header, not by the payload.
+//
+// Type mapping:
+//   Pubkey                     -> bytes32
+//   [u8; 32]                   -> bytes32
+//   u64 / u32 / u8             -> uint64 / uint32 / uint8
+//   i64                        -> int64
+//   bool                       -> bool
+//   String                     -> string
+//   Vec<u8>                    -> bytes
+//   Vec<Pubkey> / Vec<u64>     -> bytes32[] / uint64[]
+//   enums                      -> uint8, the variant index
+//   Option<Pubkey>             -> bytes32, zero when None
+//   Option<u64>                -> bool present, then uint64 (zero when None)
+// -----------------------------------------------------------------------------
+
+pub const WORD_LEN: usize = 32;
+
+type Word = [u8; WORD_LEN];
+
+enum AbiToken {
+    Word(Word),
+    Bytes(Vec<u8>),
+    Words(Vec<Word>),
+}
+
+fn uint(value: u64) -> AbiToken {
+    let mut word = [0u8; WORD_LEN];
+    word[WORD_LEN - 8..].copy_from_slice(&value.to_be_bytes());
+    AbiToken::Word(word)
+}
+
+fn int(value: i64) -> AbiToken {
+    // Two's complement, sign-extended to 256 bits
+    let mut word = if value < 0 { [0xffu8; WORD_LEN] } else { [0u8; WORD_LEN] };
+    word[WORD_LEN - 8..].copy_from_slice(&value.to_be_bytes());
+    AbiToken::Word(word)
+}
+
+fn boolean(value: bool) -> AbiToken {
+    uint(value as u64)
+}
+
+fn pubkey(value: &Pubkey) -> AbiToken {
+    AbiToken::Word(value.to_bytes())
+}
+
+fn uint_word(value: u64) -> Word {
+    let mut word = [0u8; WORD_LEN];
+    word[WORD_LEN - 8..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Head/tail encoding of a tuple: static values inline, dynamic values as an offset into the
+/// tail, where they are stored as a length followed by the padded contents.
+fn encode_tokens(tokens: &[AbiToken]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(tokens.len() * WORD_LEN);
+    let mut tail = Vec::new();
+    for token in tokens {
+        match token {
+            AbiToken::Word(word) => head.extend_from_slice(word),
+            AbiToken::Bytes(bytes) => {
+                head.extend_from_slice(&uint_word((tokens.len() * WORD_LEN + tail.len()) as u64));
+                tail.extend_from_slice(&uint_word(bytes.len() as u64));
+                tail.extend_from_slice(bytes);
+                tail.resize(tail.len() + (WORD_LEN - bytes.len() % WORD_LEN) % WORD_LEN, 0);
+            }
+            AbiToken::Words(words) => {
+                head.extend_from_slice(&uint_word((tokens.len() * WORD_LEN + tail.len()) as u64));
+                tail.extend_from_slice(&uint_word(words.len() as u64));
+                words.iter().for_each(|word| tail.extend_from_slice(word));
+            }
+        }
+    }
+    head.extend_from_slice(&tail);
+    head
+}
+
+/// Reads a tuple head slot by slot, following offsets for dynamic values.
+struct AbiDecoder<'a> {
+    data: &'a [u8],
+    slot: usize,
+}
+
+impl<'a> AbiDecoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, slot: 0 }
+    }
+
+    fn word_at(&self, offset: usize) -> Result<&'a [u8]> {
+        let end = offset.checked_add(WORD_LEN).ok_or(MsgCodecError::InvalidPayload)?;
+        Ok(self.data.get(offset..end).ok_or(MsgCodecError::BodyTooShort)?)
+    }
+
+    fn next_word(&mut self) -> Result<&'a [u8]> {
+        let word = self.word_at(self.slot * WORD_LEN)?;
+        self.slot += 1;
+        Ok(word)
+    }
+
+    fn u64_of(word: &[u8]) -> Result<u64> {
+        require!(
+            word[..WORD_LEN - 8].iter().all(|b| *b == 0),
+            MsgCodecError::InvalidPayload
+        );
+        Ok(u64::from_be_bytes(word[WORD_LEN - 8..].try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Self::u64_of(self.next_word()?)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::try_from(self.u64()?).map_err(|_| MsgCodecError::InvalidPayload)?)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(u8::try_from(self.u64()?).map_err(|_| MsgCodecError::InvalidPayload)?)
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        let word = self.next_word()?;
+        let value = i64::from_be_bytes(word[WORD_LEN - 8..].try_into().unwrap());
+        let sign = if value < 0 { 0xff } else { 0 };
+        require!(
+            word[..WORD_LEN - 8].iter().all(|b| *b == sign),
+            MsgCodecError::InvalidPayload
+        );
+        Ok(value)
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.u64()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => err!(MsgCodecError::InvalidPayload),
+        }
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        Ok(Pubkey::try_from(self.next_word()?).unwrap())
+    }
+
+    fn bytes32(&mut self) -> Result<Word> {
+        Ok(self.next_word()?.try_into().unwrap())
+    }
+
+    /// Enums travel as their variant index, which is also their Borsh encoding.
+    fn variant<T: AnchorDeserialize>(&mut self) -> Result<T> {
+        Ok(T::try_from_slice(&[self.u8()?]).map_err(|_| MsgCodecError::InvalidPayload)?)
+    }
+
+    /// Offset and element count of the dynamic value the next slot points to.
+    fn dynamic(&mut self) -> Result<(usize, usize)> {
+        let offset = Self::u64_of(self.next_word()?)? as usize;
+        let len = Self::u64_of(self.word_at(offset)?)? as usize;
+        Ok((offset + WORD_LEN, len))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>> {
+        let (start, len) = self.dynamic()?;
+        let end = start.checked_add(len).ok_or(MsgCodecError::InvalidPayload)?;
+        Ok(self.data.get(start..end).ok_or(MsgCodecError::BodyTooShort)?.to_vec())
+    }
+
+    fn string(&mut self) -> Result<String> {
+        Ok(String::from_utf8(self.bytes()?).map_err(|_| MsgCodecError::InvalidUtf8)?)
+    }
+
+    fn words(&mut self) -> Result<Vec<&'a [u8]>> {
+        let (start, len) = self.dynamic()?;
+        // Bound the count by the data actually present before allocating
+        require!(
+            len <= self.data.len().saturating_sub(start) / WORD_LEN,
+            MsgCodecError::BodyTooShort
+        );
+        (0..len).map(|i| self.word_at(start + i * WORD_LEN)).collect()
+    }
+
+    fn pubkeys(&mut self) -> Result<Vec<Pubkey>> {
+        Ok(self.words()?.into_iter().map(|w| Pubkey::try_from(w).unwrap()).collect())
+    }
+
+    fn u64s(&mut self) -> Result<Vec<u64>> {
+        self.words()?.into_iter().map(Self::u64_of).collect()
+    }
+}
+
+impl YieldMessage {
+    /// `abi.encode` of the variant's fields, see the type mapping above.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        let tokens = match self {
+            YieldMessage::DepositRequest {
+                user,
+                amount,
+                token_mint,
+                target_protocol_id,
+                min_apy,
+                slippage_tolerance,
+                deadline,
+                referrer,
+            } => vec![
+                AbiToken::Word(*user),
+                uint(*amount),
+                pubkey(token_mint),
+                uint(*target_protocol_id),
+                uint(*min_apy),
+                uint(*slippage_tolerance),
+                int(*deadline),
+                pubkey(&referrer.unwrap_or_default()),
+            ],
+            YieldMessage::WithdrawRequest {
+                user,
+                amount,
+                token_mint,
+                protocol_id,
+                target_chain_id,
+                destination_address,
+                withdraw_yield,
+                deadline,
+            } => vec![
+                AbiToken::Word(*user),
+                uint(*amount),
+                pubkey(token_mint),
+                uint(*protocol_id),
+                uint(*target_chain_id as u64),
+                AbiToken::Bytes(destination_address.clone()),
+                boolean(*withdraw_yield),
+                int(*deadline),
+            ],
+            YieldMessage::RebalanceRequest {
+                user,
+                from_protocol_id,
+                to_protocol_id,
+                amount,
+                target_chain_id,
+                min_output_amount,
+                deadline,
+            } => vec![
+                AbiToken::Word(*user),
+                uint(*from_protocol_id),
+                uint(*to_protocol_id),
+                uint(*amount),
+                uint(*target_chain_id as u64),
+                uint(*min_output_amount),
+                int(*deadline),
+            ],
+            YieldMessage::YieldUpdate {
+                protocol_id,
+                protocol_name,
+                new_apy,
+                tvl,
+                available_capacity,
+                risk_score,
+                last_harvest_time,
+                update_timestamp,
+            } => vec![
+                uint(*protocol_id),
+                AbiToken::Bytes(protocol_name.as_bytes().to_vec()),
+                uint(*new_apy),
+                uint(*tvl),
+                uint(*available_capacity),
+                uint(*risk_score as u64),
+                int(*last_harvest_time),
+                int(*update_timestamp),
+            ],
+            YieldMessage::PositionSync {
+                user,
+                protocol_id,
+                principal_amount,
+                yield_earned,
+                total_shares,
+                last_compound_time,
+                position_health,
+                sync_timestamp,
+            } => vec![
+                AbiToken::Word(*user),
+                uint(*protocol_id),
+                uint(*principal_amount),
+                uint(*yield_earned),
+                uint(*total_shares),
+                int(*last_compound_time),
+                uint(*position_health as u64),
+                int(*sync_timestamp),
+            ],
+            YieldMessage::YieldDistribution {
+                protocol_id,
+                total_yield,
+                distribution_rate,
+                eligible_users,
+                per_user_yield,
+                distribution_timestamp,
+            } => vec![
+                uint(*protocol_id),
+                uint(*total_yield),
+                uint(*distribution_rate),
+                AbiToken::Words(eligible_users.iter().map(|u| u.to_bytes()).collect()),
+                AbiToken::Words(per_user_yield.iter().map(|y| uint_word(*y)).collect()),
+                int(*distribution_timestamp),
+            ],
+            YieldMessage::EmergencyAction {
+                action_type,
+                protocol_id,
+                reason,
+                initiated_by,
+                timestamp,
+            } => vec![
+                uint(action_type.clone() as u64),
+                boolean(protocol_id.is_some()),
+                uint(protocol_id.unwrap_or_default()),
+                AbiToken::Bytes(reason.as_bytes().to_vec()),
+                pubkey(initiated_by),
+                int(*timestamp),
+            ],
+            YieldMessage::ProtocolConfig {
+                protocol_id,
+                config_type,
+                config_data,
+                effective_timestamp,
+            } => vec![
+                uint(*protocol_id),
+                uint(config_type.clone() as u64),
+                AbiToken::Bytes(config_data.clone()),
+                int(*effective_timestamp),
+            ],
+            YieldMessage::GovernanceVote {
+                proposal_id,
+                voter,
+                vote_weight,
+                vote_choice,
+                voting_power,
+                timestamp,
+            } => vec![
+                uint(*proposal_id),
+                AbiToken::Word(*voter),
+                uint(*vote_weight),
+                boolean(*vote_choice),
+                uint(*voting_power),
+                int(*timestamp),
+            ],
+            YieldMessage::LiquidationNotice {
+                user,
+                protocol_id,
+                liquidated_amount,
+                liquidation_penalty,
+                liquidator,
+                timestamp,
+            } => vec![
+                AbiToken::Word(*user),
+                uint(*protocol_id),
+                uint(*liquidated_amount),
+                uint(*liquidation_penalty),
+                pubkey(liquidator),
+                int(*timestamp),
+            ],
+            YieldMessage::FeeCollection {
+                protocol_id,
+                fee_type,
+                amount,
+                token_mint,
+                collected_from,
+                timestamp,
+            } => vec![
+                uint(*protocol_id),
+                uint(fee_type.clone() as u64),
+                uint(*amount),
+                pubkey(token_mint),
+                AbiToken::Words(collected_from.iter().map(|u| u.to_bytes()).collect()),
+                int(*timestamp),
+            ],
+            YieldMessage::PriceUpdate {
+                token_mint,
+                price,
+                confidence,
+                timestamp,
+                oracle_source,
+            } => vec![
+                pubkey(token_mint),
+                uint(*price),
+                uint(*confidence),
+                int(*timestamp),
+                AbiToken::Bytes(oracle_source.as_bytes().to_vec()),
+            ],
+            YieldMessage::MessageAck {
+                nonce,
+                message_id,
+                success,
+                timestamp,
+            } => vec![
+                uint(*nonce),
+                AbiToken::Word(*message_id),
+                boolean(*success),
+                int(*timestamp),
+            ],
+            YieldMessage::WithdrawalRelease {
+                user,
+                amount,
+                token_mint,
+                protocol_id,
+                destination_address,
+                timestamp,
+            } => vec![
+                AbiToken::Word(*user),
+                uint(*amount),
+                pubkey(token_mint),
+                uint(*protocol_id),
+                AbiToken::Bytes(destination_address.clone()),
+                int(*timestamp),
+            ],
+        };
+        encode_tokens(&tokens)
+    }
+
+    /// Decodes an ABI payload of the variant with message type `msg_type`. Struct literal
+    /// fields are evaluated in the order written, which is the encoding order.
+    pub fn abi_decode(msg_type: u8, data: &[u8]) -> Result<Self> {
+        let mut d = AbiDecoder::new(data);
+        let message = match msg_type {
+            DEPOSIT_REQUEST_TYPE => YieldMessage::DepositRequest {
+                user: d.bytes32()?,
+                amount: d.u64()?,
+                token_mint: d.pubkey()?,
+                target_protocol_id: d.u64()?,
+                min_apy: d.u64()?,
+                slippage_tolerance: d.u64()?,
+                deadline: d.i64()?,
+                referrer: Some(d.pubkey()?).filter(|r| *r != Pubkey::default()),
+            },
+            WITHDRAW_REQUEST_TYPE => YieldMessage::WithdrawRequest {
+                user: d.bytes32()?,
+                amount: d.u64()?,
+                token_mint: d.pubkey()?,
+                protocol_id: d.u64()?,
+                target_chain_id: d.u32()?,
+                destination_address: d.bytes()?,
+                withdraw_yield: d.bool()?,
+                deadline: d.i64()?,
+            },
+            REBALANCE_REQUEST_TYPE => YieldMessage::RebalanceRequest {
+                user: d.bytes32()?,
+                from_protocol_id: d.u64()?,
+                to_protocol_id: d.u64()?,
+                amount: d.u64()?,
+                target_chain_id: d.u32()?,
+                min_output_amount: d.u64()?,
+                deadline: d.i64()?,
+            },
+            YIELD_UPDATE_TYPE => YieldMessage::YieldUpdate {
+                protocol_id: d.u64()?,
+                protocol_name: d.string()?,
+                new_apy: d.u64()?,
+                tvl: d.u64()?,
+                available_capacity: d.u64()?,
+                risk_score: d.u8()?,
+                last_harvest_time: d.i64()?,
+                update_timestamp: d.i64()?,
+            },
+            POSITION_SYNC_TYPE => YieldMessage::PositionSync {
+                user: d.bytes32()?,
+                protocol_id: d.u64()?,
+                principal_amount: d.u64()?,
+                yield_earned: d.u64()?,
+                total_shares: d.u64()?,
+                last_compound_time: d.i64()?,
+                position_health: d.u8()?,
+                sync_timestamp: d.i64()?,
+            },
+            YIELD_DISTRIBUTION_TYPE => YieldMessage::YieldDistribution {
+                protocol_id: d.u64()?,
+                total_yield: d.u64()?,
+                distribution_rate: d.u64()?,
+                eligible_users: d.pubkeys()?,
+                per_user_yield: d.u64s()?,
+                distribution_timestamp: d.i64()?,
+            },
+            EMERGENCY_ACTION_TYPE => {
+                let action_type: EmergencyActionType = d.variant()?;
+                let has_protocol_id = d.bool()?;
+                let protocol_id = d.u64()?;
+                YieldMessage::EmergencyAction {
+                    action_type,
+                    protocol_id: has_protocol_id.then_some(protocol_id),
+                    reason: d.string()?,
+                    initiated_by: d.pubkey()?,
+                    timestamp: d.i64()?,
+                }
+            }
+            PROTOCOL_CONFIG_TYPE => YieldMessage::ProtocolConfig {
+                protocol_id: d.u64()?,
+                config_type: d.variant::<ProtocolConfigType>()?,
+                config_data: d.bytes()?,
+                effective_timestamp: d.i64()?,
+            },
+            GOVERNANCE_VOTE_TYPE => YieldMessage::GovernanceVote {
+                proposal_id: d.u64()?,
+                voter: d.bytes32()?,
+                vote_weight: d.u64()?,
+                vote_choice: d.bool()?,
+                voting_power: d.u64()?,
+                timestamp: d.i64()?,
+            },
+            LIQUIDATION_NOTICE_TYPE => YieldMessage::LiquidationNotice {
+                user: d.bytes32()?,
+                protocol_id: d.u64()?,
+                liquidated_amount: d.u64()?,
+                liquidation_penalty: d.u64()?,
+                liquidator: d.pubkey()?,
+                timestamp: d.i64()?,
+            },
+            FEE_COLLECTION_TYPE => YieldMessage::FeeCollection {
+                protocol_id: d.u64()?,
+                fee_type: d.variant::<FeeType>()?,
+                amount: d.u64()?,
+                token_mint: d.pubkey()?,
+                collected_from: d.pubkeys()?,
+                timestamp: d.i64()?,
+            },
+            PRICE_UPDATE_TYPE => YieldMessage::PriceUpdate {
+                token_mint: d.pubkey()?,
+                price: d.u64()?,
+                confidence: d.u64()?,
+                timestamp: d.i64()?,
+                oracle_source: d.string()?,
+            },
+            MESSAGE_ACK_TYPE => YieldMessage::MessageAck {
+                nonce: d.u64()?,
+                message_id: d.bytes32()?,
+                success: d.bool()?,
+                timestamp: d.i64()?,
+            },
+            WITHDRAWAL_RELEASE_TYPE => YieldMessage::WithdrawalRelease {
+                user: d.bytes32()?,
+                amount: d.u64()?,
+                token_mint: d.pubkey()?,
+                protocol_id: d.u64()?,
+                destination_address: d.bytes()?,
+                timestamp: d.i64()?,
+            },
+            _ => return err!(MsgCodecError::UnsupportedMessageType),
+        };
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(words: &[&str]) -> Vec<u8> {
+        words
+            .concat()
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+            .collect()
+    }
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn round_trip(message: YieldMessage) {
+        let encoded = message.abi_encode();
+        assert_eq!(encoded.len() % WORD_LEN, 0);
+        let decoded = YieldMessage::abi_decode(message.get_message_type(), &encoded).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn test_round_trip_every_variant() {
+        round_trip(YieldMessage::DepositRequest {
+            user: [1; 32],
+            amount: 1_000_000,
+            token_mint: key(2),
+            target_protocol_id: 1,
+            min_apy: 500,
+            slippage_tolerance: 100,
+            deadline: 1_700_000_000,
+            referrer: Some(key(3)),
+        });
+        round_trip(YieldMessage::WithdrawRequest {
+            user: [1; 32],
+            amount: 5,
+            token_mint: key(2),
+            protocol_id: 2,
+            target_chain_id: 40161,
+            destination_address: vec![0xab; 20],
+            withdraw_yield: true,
+            deadline: 1_700_000_000,
+        });
+        round_trip(YieldMessage::RebalanceRequest {
+            user: [1; 32],
+            from_protocol_id: 1,
+            to_protocol_id: 2,
+            amount: 10,
+            target_chain_id: 40168,
+            min_output_amount: 9,
+            deadline: 1_700_000_000,
+        });
+        round_trip(YieldMessage::YieldUpdate {
+            protocol_id: 1,
+            protocol_name: "a protocol name longer than one word".to_string(),
+            new_apy: 750,
+            tvl: u64::MAX,
+            available_capacity: 0,
+            risk_score: 3,
+            last_harvest_time: -1,
+            update_timestamp: i64::MIN,
+        });
+        round_trip(YieldMessage::PositionSync {
+            user: [4; 32],
+            protocol_id: 1,
+            principal_amount: 100,
+            yield_earned: 7,
+            total_shares: 95,
+            last_compound_time: 1_699_999_000,
+            position_health: 88,
+            sync_timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::YieldDistribution {
+            protocol_id: 1,
+            total_yield: 0,
+            distribution_rate: 0,
+            eligible_users: vec![],
+            per_user_yield: vec![],
+            distribution_timestamp: 0,
+        });
+        round_trip(YieldMessage::EmergencyAction {
+            action_type: EmergencyActionType::HaltDeposits,
+            protocol_id: Some(0),
+            reason: String::new(),
+            initiated_by: key(5),
+            timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::EmergencyAction {
+            action_type: EmergencyActionType::Pause,
+            protocol_id: None,
+            reason: "oracle outage".to_string(),
+            initiated_by: key(5),
+            timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::ProtocolConfig {
+            protocol_id: 3,
+            config_type: ProtocolConfigType::FeeStructure,
+            config_data: vec![1, 2, 3],
+            effective_timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::GovernanceVote {
+            proposal_id: 9,
+            voter: [6; 32],
+            vote_weight: 1_000,
+            vote_choice: false,
+            voting_power: 2_000,
+            timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::LiquidationNotice {
+            user: [7; 32],
+            protocol_id: 1,
+            liquidated_amount: 50,
+            liquidation_penalty: 5,
+            liquidator: key(8),
+            timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::FeeCollection {
+            protocol_id: 1,
+            fee_type: FeeType::Deposit,
+            amount: 12,
+            token_mint: key(2),
+            collected_from: vec![key(9), key(10), key(11)],
+            timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::MessageAck {
+            nonce: 4,
+            message_id: [12u8; 32],
+            success: true,
+            timestamp: 1_700_000_000,
+        });
+        round_trip(YieldMessage::WithdrawalRelease {
+            user: [1; 32],
+            amount: 5,
+            token_mint: key(2),
+            protocol_id: 2,
+            destination_address: vec![0xab; 20],
+            timestamp: 1_700_000_000,
+        });
+    }
+
+    // Golden vectors: the same values encoded with Solidity's abi.encode must give these bytes.
+    // test/foundry/YieldMsgCodec.t.sol decodes each of them with the matching Solidity types.
+
+    // An EVM address as a 32-byte user, left padded like bytes32(uint256(uint160(addr)))
+    fn evm_user() -> [u8; 32] {
+        let mut user = [0u8; 32];
+        user[12..].fill(0x33);
+        user
+    }
+
+    #[test]
+    fn test_deposit_request_golden_vector() {
+        let message = YieldMessage::DepositRequest {
+            user: evm_user(),
+            amount: 1_000_000,
+            token_mint: key(0x44),
+            target_protocol_id: 2,
+            min_apy: 500,
+            slippage_tolerance: 50,
+            deadline: 1_700_000_000,
+            referrer: None,
+        };
+        let expected = hex(&[
+            "0000000000000000000000003333333333333333333333333333333333333333",
+            "00000000000000000000000000000000000000000000000000000000000f4240",
+            "4444444444444444444444444444444444444444444444444444444444444444",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "00000000000000000000000000000000000000000000000000000000000001f4",
+            "0000000000000000000000000000000000000000000000000000000000000032",
+            "000000000000000000000000000000000000000000000000000000006553f100",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ]);
+        assert_eq!(message.abi_encode(), expected);
+        assert_eq!(YieldMessage::abi_decode(DEPOSIT_REQUEST_TYPE, &expected).unwrap(), message);
+    }
+
+    #[test]
+    fn test_withdraw_request_golden_vector() {
+        let message = YieldMessage::WithdrawRequest {
+            user: evm_user(),
+            amount: 250_000,
+            token_mint: key(0x44),
+            protocol_id: 2,
+            target_chain_id: 30101,
+            destination_address: vec![0xab; 20],
+            withdraw_yield: true,
+            deadline: 1_700_000_000,
+        };
+        let expected = hex(&[
+            "0000000000000000000000003333333333333333333333333333333333333333",
+            "000000000000000000000000000000000000000000000000000000000003d090",
+            "4444444444444444444444444444444444444444444444444444444444444444",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000007595",
+            "0000000000000000000000000000000000000000000000000000000000000100",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "000000000000000000000000000000000000000000000000000000006553f100",
+            "0000000000000000000000000000000000000000000000000000000000000014",
+            "abababababababababababababababababababab000000000000000000000000",
+        ]);
+        assert_eq!(message.abi_encode(), expected);
+        assert_eq!(YieldMessage::abi_decode(WITHDRAW_REQUEST_TYPE, &expected).unwrap(), message);
+    }
+
+    #[test]
+    fn test_message_ack_golden_vector() {
+        let message = YieldMessage::MessageAck {
+            nonce: 7,
+            message_id: hex(&["2a0772f1e648cc32eff4373bad767edb7648f3b7a8c12121c574d910a1832eb6"])
+                .try_into()
+                .unwrap(),
+            success: true,
+            timestamp: 1_700_000_000,
+        };
+        let expected = hex(&[
+            "0000000000000000000000000000000000000000000000000000000000000007",
+            "2a0772f1e648cc32eff4373bad767edb7648f3b7a8c12121c574d910a1832eb6",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "000000000000000000000000000000000000000000000000000000006553f100",
+        ]);
+        assert_eq!(message.abi_encode(), expected);
+        assert_eq!(YieldMessage::abi_decode(MESSAGE_ACK_TYPE, &expected).unwrap(), message);
+    }
+
+    #[test]
+    fn test_price_update_golden_vector() {
+        let message = YieldMessage::PriceUpdate {
+            token_mint: key(0x11),
+            price: 42_000_000,
+            confidence: 100,
+            timestamp: 1_700_000_000,
+            oracle_source: "pyth".to_string(),
+        };
+        let expected = hex(&[
+            "1111111111111111111111111111111111111111111111111111111111111111",
+            "000000000000000000000000000000000000000000000000000000000280de80",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "000000000000000000000000000000000000000000000000000000006553f100",
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "7079746800000000000000000000000000000000000000000000000000000000",
+        ]);
+        assert_eq!(message.abi_encode(), expected);
+        assert_eq!(YieldMessage::abi_decode(PRICE_UPDATE_TYPE, &expected).unwrap(), message);
+    }
+
+    #[test]
+    fn test_yield_distribution_golden_vector() {
+        let message = YieldMessage::YieldDistribution {
+            protocol_id: 1,
+            total_yield: 300,
+            distribution_rate: 50,
+            eligible_users: vec![key(0xaa), key(0xbb)],
+            per_user_yield: vec![100, 200],
+            distribution_timestamp: 1_700_000_000,
+        };
+        let expected = hex(&[
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "000000000000000000000000000000000000000000000000000000000000012c",
+            "0000000000000000000000000000000000000000000000000000000000000032",
+            "00000000000000000000000000000000000000000000000000000000000000c0",
+            "0000000000000000000000000000000000000000000000000000000000000120",
+            "000000000000000000000000000000000000000000000000000000006553f100",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "00000000000000000000000000000000000000000000000000000000000000c8",
+        ]);
+        assert_eq!(message.abi_encode(), expected);
+        assert_eq!(YieldMessage::abi_decode(YIELD_DISTRIBUTION_TYPE, &expected).unwrap(), message);
+    }
+
+    #[test]
+    fn test_emergency_action_golden_vector() {
+        let message = YieldMessage::EmergencyAction {
+            action_type: EmergencyActionType::HaltDeposits,
+            protocol_id: Some(7),
+            reason: "exploit".to_string(),
+            initiated_by: key(0x22),
+            timestamp: -1,
+        };
+        let expected = hex(&[
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000007",
+            "00000000000000000000000000000000000000000000000000000000000000c0",
+            "2222222222222222222222222222222222222222222222222222222222222222",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "0000000000000000000000000000000000000000000000000000000000000007",
+            "6578706c6f697400000000000000000000000000000000000000000000000000",
+        ]);
+        assert_eq!(message.abi_encode(), expected);
+        assert_eq!(YieldMessage::abi_decode(EMERGENCY_ACTION_TYPE, &expected).unwrap(), message);
+    }
+
+    #[test]
+    fn test_malformed_payloads_are_rejected() {
+        let message = YieldMessage::GovernanceVote {
+            proposal_id: 1,
+            voter: [1; 32],
+            vote_weight: 1,
+            vote_choice: true,
+            voting_power: 1,
+            timestamp: 1,
+        };
+        let encoded = message.abi_encode();
+        assert!(YieldMessage::abi_decode(WITHDRAWAL_RELEASE_TYPE + 1, &encoded).is_err());
+        let truncated = &encoded[..encoded.len() - 1];
+        assert!(YieldMessage::abi_decode(GOVERNANCE_VOTE_TYPE, truncated).is_err());
+
+        // A bool that isn't 0 or 1
+        let mut bad_bool = encoded.clone();
+        bad_bool[4 * WORD_LEN - 1] = 2;
+        assert!(YieldMessage::abi_decode(GOVERNANCE_VOTE_TYPE, &bad_bool).is_err());
+
+        // A uint64 with high bits set
+        let mut overflow = encoded;
+        overflow[0] = 1;
+        assert!(YieldMessage::abi_decode(GOVERNANCE_VOTE_TYPE, &overflow).is_err());
+    }
+}
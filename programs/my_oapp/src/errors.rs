@@ -32,4 +32,38 @@ pub enum MyOAppError {
     OraclePriceStale,
     #[msg("Cross-chain message failed")]
     CrossChainMessageFailed,
+    #[msg("Too many protocols")]
+    TooManyProtocols,
+    #[msg("Account does not match the expected address")]
+    InvalidAccount,
+    #[msg("Action is not supported on this chain")]
+    UnsupportedAction,
+    #[msg("Config is not effective yet")]
+    ConfigNotEffective,
+    #[msg("Message has already been processed")]
+    MessageAlreadyProcessed,
+    #[msg("Message is still inside its replay window")]
+    MessageNotExpired,
+    #[msg("Invalid inbound nonce")]
+    InvalidNonce,
+    #[msg("Enforced options don't fit in the peer account")]
+    EnforcedOptionsTooLarge,
+    #[msg("Peer address is unchanged")]
+    PeerAddressUnchanged,
+    #[msg("Privileged message is missing its guardian signature")]
+    MissingGuardianSignature,
+    #[msg("Guardian signature is not verified in this transaction")]
+    InvalidGuardianSignature,
+    #[msg("Deposit has not been acknowledged by the destination")]
+    DepositNotAcknowledged,
+    #[msg("Deposit can't be refunded before its deadline and grace period have passed")]
+    DepositNotExpired,
+    #[msg("Deposit was acknowledged by the destination")]
+    DepositAlreadyAcknowledged,
+    #[msg("Signature doesn't prove ownership of the address")]
+    InvalidIdentityProof,
+    #[msg("Deposit exceeds the peer's credit line")]
+    CreditLineExceeded,
+    #[msg("Math overflow")]
+    MathOverflow,
 }
\ No newline at end of file
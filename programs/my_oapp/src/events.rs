@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+/// A composed step ran with `COMPOSE_REQUEST_SYNC` set. Anyone can answer it with
+/// `send_position_sync`.
+#[event]
+pub struct PositionSyncRequested {
+    pub dst_eid: u32,
+    pub user: [u8; 32],
+    pub protocol_id: u64,
+    pub guid: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// A withdrawal was redeemed for a destination on another chain. Its assets wait in remote
+/// custody, counted in `RemotePosition::pending_withdrawal`, for the return leg.
+#[event]
+pub struct WithdrawalReturnRequested {
+    pub dst_eid: u32,
+    pub user: [u8; 32],
+    pub protocol_id: u64,
+    pub amount: u64,
+    pub destination_address: Vec<u8>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PeerInitialized {
+    pub eid: u32,
+    pub peer_address: [u8; 32],
+    pub ordered_nonce: bool,
+}
+
+#[event]
+pub struct PeerAddressUpdated {
+    pub eid: u32,
+    pub old_peer_address: [u8; 32],
+    pub peer_address: [u8; 32],
+}
+
+#[event]
+pub struct EnforcedOptionsUpdated {
+    pub eid: u32,
+    pub send: Vec<u8>,
+    pub send_and_call: Vec<u8>,
+}
+
+#[event]
+pub struct PeerClosed {
+    pub eid: u32,
+    pub peer_address: [u8; 32],
+}
+
+#[event]
+pub struct MessageTypeOptionsUpdated {
+    pub eid: u32,
+    pub msg_type: u8,
+    pub send: Vec<u8>,
+    pub send_and_call: Vec<u8>,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub guardian: Option<Pubkey>,
+}
+
+#[event]
+pub struct IdentityLinked {
+    pub eid: u32,
+    pub address: [u8; 32],
+    pub solana: Pubkey,
+}
+
+#[event]
+pub struct IdentityUnlinked {
+    pub eid: u32,
+    pub address: [u8; 32],
+    pub solana: Pubkey,
+}
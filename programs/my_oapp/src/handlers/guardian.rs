@@ -0,0 +1,154 @@
+use super::{pda, readonly, Inbound};
+use crate::errors::MyOAppError;
+use crate::msg_codec::{is_privileged_message, CrossChainMessage};
+use crate::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use oapp::endpoint_cpi::LzAccount;
+
+// The ed25519 precompile checks its signatures when the transaction is processed, so a
+// signature that appears in one of its instructions earlier in the transaction is valid.
+//
+// Ed25519 instruction data:
+// | 1 byte count | 1 byte padding | count * 14 byte offsets | signature, key and message data |
+// Offsets are little-endian u16s: signature offset, signature ix index, public key offset,
+// public key ix index, message offset, message size, message ix index. An ix index of
+// u16::MAX points into the ed25519 instruction itself.
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const SIGNATURE_LEN: usize = 64;
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Accounts `authenticate` reads for a privileged message: the guardian config and the
+/// instructions sysvar.
+pub fn accounts() -> Vec<LzAccount> {
+    vec![readonly(pda(&[GUARDIAN_SEED])), readonly(instructions_sysvar::ID)]
+}
+
+/// Checks the guardian signature of a privileged message before it is dispatched. Other
+/// messages read no accounts. The message id must also match the message, or a signature
+/// could be moved onto a different payload. Without a guardian the message still dispatches,
+/// but the remote admin won't sign for it.
+pub fn authenticate<'info>(inbound: &mut Inbound<'info>, message: &CrossChainMessage) -> Result<()> {
+    if !is_privileged_message(message.header.msg_type) {
+        return Ok(());
+    }
+    let config = inbound.next()?;
+    require_keys_eq!(config.key(), pda(&[GUARDIAN_SEED]), MyOAppError::InvalidAccount);
+    let instructions = inbound.next()?;
+    require_keys_eq!(instructions.key(), instructions_sysvar::ID, MyOAppError::InvalidAccount);
+
+    // No guardian has been set up yet
+    if config.data_is_empty() {
+        return Ok(());
+    }
+    let Some(guardian) = Account::<GuardianConfig>::try_from(config)?.guardian else {
+        return Ok(());
+    };
+
+    let signature = message
+        .signature
+        .as_deref()
+        .ok_or(MyOAppError::MissingGuardianSignature)?;
+    require!(
+        message.generate_message_id() == message.header.message_id,
+        MyOAppError::InvalidGuardianSignature
+    );
+
+    let current = load_current_index_checked(instructions)?;
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, instructions)?;
+        if ix.program_id == ed25519_program::ID
+            && verifies(&ix.data, &guardian, &message.header.message_id, signature)
+        {
+            inbound.guardian_verified = true;
+            return Ok(());
+        }
+    }
+    err!(MyOAppError::InvalidGuardianSignature)
+}
+
+/// Whether ed25519 instruction `data` checks `signature` by `signer` over `message`, with
+/// all three inline.
+fn verifies(data: &[u8], signer: &Pubkey, message: &[u8], signature: &[u8]) -> bool {
+    if signature.len() != SIGNATURE_LEN {
+        return false;
+    }
+    let Some(&count) = data.first() else {
+        return false;
+    };
+    (0..count as usize).any(|i| {
+        let start = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_SIZE;
+        let Some(offsets) = data.get(start..start + SIGNATURE_OFFSETS_SIZE) else {
+            return false;
+        };
+        let field = |n: usize| u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]);
+        let inline = [field(1), field(3), field(6)]
+            .iter()
+            .all(|&ix| ix == CURRENT_INSTRUCTION);
+        let slice = |offset: u16, len: usize| data.get(offset as usize..offset as usize + len);
+
+        inline
+            && slice(field(0), SIGNATURE_LEN) == Some(signature)
+            && slice(field(2), 32) == Some(signer.as_ref())
+            && field(5) as usize == message.len()
+            && slice(field(4), message.len()) == Some(message)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Data laid out like `new_ed25519_instruction`: offsets, key, signature, message.
+    fn ed25519_data(signer: &Pubkey, signature: &[u8], message: &[u8]) -> Vec<u8> {
+        let key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+        let signature_offset = key_offset + 32;
+        let message_offset = signature_offset + SIGNATURE_LEN;
+        let mut data = vec![1, 0];
+        for field in [
+            signature_offset as u16,
+            CURRENT_INSTRUCTION,
+            key_offset as u16,
+            CURRENT_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_matching_signature_verifies() {
+        let guardian = Pubkey::new_unique();
+        let data = ed25519_data(&guardian, &[5u8; 64], &[7u8; 32]);
+        assert!(verifies(&data, &guardian, &[7u8; 32], &[5u8; 64]));
+    }
+
+    #[test]
+    fn test_other_signer_message_or_signature_is_rejected() {
+        let guardian = Pubkey::new_unique();
+        let data = ed25519_data(&guardian, &[5u8; 64], &[7u8; 32]);
+        assert!(!verifies(&data, &Pubkey::new_unique(), &[7u8; 32], &[5u8; 64]));
+        assert!(!verifies(&data, &guardian, &[8u8; 32], &[5u8; 64]));
+        assert!(!verifies(&data, &guardian, &[7u8; 32], &[6u8; 64]));
+        assert!(!verifies(&data[..data.len() - 1], &guardian, &[7u8; 32], &[5u8; 64]));
+    }
+
+    #[test]
+    fn test_data_in_other_instructions_is_rejected() {
+        let guardian = Pubkey::new_unique();
+        let mut data = ed25519_data(&guardian, &[5u8; 64], &[7u8; 32]);
+        // Point the message at instruction 0 instead of the ed25519 instruction
+        data[14..16].copy_from_slice(&0u16.to_le_bytes());
+        assert!(!verifies(&data, &guardian, &[7u8; 32], &[5u8; 64]));
+    }
+}
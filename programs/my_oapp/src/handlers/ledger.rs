@@ -0,0 +1,342 @@
+use super::{pda, readonly, registry_address, writable, Inbound};
+use crate::errors::MyOAppError;
+use crate::msg_codec::{MsgCodecError, YieldMessage};
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint_cpi::LzAccount;
+
+// Reports about state on other chains. They only update local mirror and ledger accounts, and
+// a report older than what is already stored is ignored rather than rejected so it still gets
+// cleared.
+
+pub fn position_sync<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::PositionSync {
+        user,
+        protocol_id,
+        principal_amount,
+        yield_earned,
+        total_shares,
+        last_compound_time,
+        position_health,
+        sync_timestamp,
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let mut mirror = load_mirror(inbound, *user, *protocol_id)?;
+    if *sync_timestamp > mirror.sync_timestamp {
+        mirror.principal = *principal_amount;
+        mirror.yield_earned = *yield_earned;
+        mirror.shares = *total_shares;
+        mirror.last_compound_time = *last_compound_time;
+        mirror.position_health = *position_health;
+        mirror.sync_timestamp = *sync_timestamp;
+    }
+    mirror.exit(&crate::ID)
+}
+
+pub fn yield_distribution<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::YieldDistribution {
+        protocol_id,
+        total_yield,
+        distribution_rate,
+        eligible_users,
+        per_user_yield,
+        distribution_timestamp,
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+    require!(
+        eligible_users.len() == per_user_yield.len()
+            && per_user_yield
+                .iter()
+                .try_fold(0u64, |sum, y| sum.checked_add(*y))
+                .is_some_and(|sum| sum <= *total_yield),
+        MsgCodecError::InvalidPayload
+    );
+
+    let mut stats = load_stats(inbound, *protocol_id)?;
+    stats.total_yield_distributed = stats
+        .total_yield_distributed
+        .checked_add(*total_yield)
+        .ok_or(MyOAppError::MathOverflow)?;
+    if *distribution_timestamp > stats.last_distribution {
+        stats.last_distribution_rate = *distribution_rate;
+        stats.last_distribution = *distribution_timestamp;
+    }
+    stats.exit(&crate::ID)
+}
+
+/// Counts a voter once per proposal, weighted by the principal the voter holds here across
+/// the registered protocols. The weight and voting power in the message aren't trusted, and a
+/// repeated vote is ignored.
+pub fn governance_vote<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::GovernanceVote { proposal_id, voter, vote_choice, timestamp, .. } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let proposal_id = *proposal_id;
+    let voter = UniversalAddress::new(inbound.src_eid, *voter);
+    let mut tally = inbound.load_or_init(&[PROPOSAL_SEED, &proposal_id.to_be_bytes()], |bump| {
+        ProposalTally {
+            proposal_id,
+            yes_weight: 0,
+            no_weight: 0,
+            votes: 0,
+            last_vote: 0,
+            bump,
+        }
+    })?;
+    let mut first_vote = false;
+    let mut record = inbound.load_or_init(
+        &[VOTE_RECORD_SEED, &proposal_id.to_be_bytes(), &voter.eid.to_be_bytes(), &voter.address],
+        |bump| {
+            first_vote = true;
+            VoteRecord {
+                proposal_id,
+                voter,
+                weight: 0,
+                vote_choice: *vote_choice,
+                timestamp: *timestamp,
+                bump,
+            }
+        },
+    )?;
+    if !first_vote {
+        return Ok(());
+    }
+
+    let registry = inbound.registry()?;
+    let mut weight = 0u64;
+    for route in &registry.routes {
+        let position: Option<Account<RemotePosition>> = inbound.load_if_exists(&[
+            REMOTE_POSITION_SEED,
+            &voter.eid.to_be_bytes(),
+            &voter.address,
+            &route.protocol_id.to_be_bytes(),
+        ])?;
+        if let Some(position) = position {
+            weight = weight.checked_add(position.principal).ok_or(MyOAppError::MathOverflow)?;
+        }
+    }
+
+    let side = if *vote_choice { &mut tally.yes_weight } else { &mut tally.no_weight };
+    *side = side.checked_add(weight).ok_or(MyOAppError::MathOverflow)?;
+    tally.votes = tally.votes.checked_add(1).ok_or(MyOAppError::MathOverflow)?;
+    tally.last_vote = tally.last_vote.max(*timestamp);
+    record.weight = weight;
+    record.exit(&crate::ID)?;
+    tally.exit(&crate::ID)
+}
+
+/// The tally and the voter's vote record, then the registry and the voter's position in each
+/// registered protocol, whose principal is the vote's weight.
+pub fn governance_vote_accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    proposal_id: u64,
+    voter: [u8; 32],
+) -> Vec<LzAccount> {
+    let mut accounts = vec![
+        writable(pda(&[PROPOSAL_SEED, &proposal_id.to_be_bytes()])),
+        writable(pda(&[
+            VOTE_RECORD_SEED,
+            &proposal_id.to_be_bytes(),
+            &src_eid.to_be_bytes(),
+            voter.as_ref(),
+        ])),
+        readonly(registry_address()),
+    ];
+    accounts.extend(registry.routes.iter().map(|route| {
+        readonly(pda(&[
+            REMOTE_POSITION_SEED,
+            &src_eid.to_be_bytes(),
+            voter.as_ref(),
+            &route.protocol_id.to_be_bytes(),
+        ]))
+    }));
+    accounts
+}
+
+pub fn liquidation_notice<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::LiquidationNotice {
+        user,
+        protocol_id,
+        liquidated_amount,
+        liquidation_penalty,
+        ..
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let mut mirror = load_mirror(inbound, *user, *protocol_id)?;
+    mirror.principal = mirror.principal.saturating_sub(*liquidated_amount);
+    mirror.liquidated_amount = mirror
+        .liquidated_amount
+        .checked_add(*liquidated_amount)
+        .ok_or(MyOAppError::MathOverflow)?;
+    mirror.exit(&crate::ID)?;
+
+    let mut stats = load_stats(inbound, *protocol_id)?;
+    stats.total_liquidated = stats
+        .total_liquidated
+        .checked_add(*liquidated_amount)
+        .ok_or(MyOAppError::MathOverflow)?;
+    stats.total_liquidation_penalty = stats
+        .total_liquidation_penalty
+        .checked_add(*liquidation_penalty)
+        .ok_or(MyOAppError::MathOverflow)?;
+    stats.exit(&crate::ID)
+}
+
+pub fn fee_collection<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::FeeCollection {
+        protocol_id,
+        fee_type,
+        amount,
+        ..
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let mut stats = load_stats(inbound, *protocol_id)?;
+    let collected = &mut stats.fees_collected[fee_type.clone() as usize];
+    *collected = collected.checked_add(*amount).ok_or(MyOAppError::MathOverflow)?;
+    stats.exit(&crate::ID)
+}
+
+pub fn price_update<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::PriceUpdate {
+        token_mint,
+        price,
+        confidence,
+        timestamp,
+        oracle_source,
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+    require!(oracle_source.len() <= MAX_ORACLE_SOURCE_LEN, MsgCodecError::InvalidPayload);
+
+    let token_mint = *token_mint;
+    let mut feed = inbound.load_or_init(&[PRICE_FEED_SEED, token_mint.as_ref()], |bump| PriceFeed {
+        token_mint,
+        price: 0,
+        confidence: 0,
+        timestamp: 0,
+        src_eid: 0,
+        oracle_source: String::new(),
+        bump,
+    })?;
+    if *timestamp > feed.timestamp {
+        feed.price = *price;
+        feed.confidence = *confidence;
+        feed.timestamp = *timestamp;
+        feed.src_eid = inbound.src_eid;
+        feed.oracle_source = oracle_source.clone();
+    }
+    feed.exit(&crate::ID)
+}
+
+/// Settles the outbound ledger entry of an acknowledged message, see `OutboundMessage::settle`.
+/// An ack for an entry that doesn't exist changes nothing.
+pub fn message_ack<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::MessageAck { nonce, message_id, success, .. } = message else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let info = inbound.next()?;
+    let expected = outbound_message_address(inbound.src_eid, *nonce);
+    require_keys_eq!(info.key(), expected, MyOAppError::InvalidAccount);
+    if info.data_is_empty() {
+        return Ok(());
+    }
+    let mut entry: Account<OutboundMessage> = Account::try_from(info)?;
+    entry.settle(message_id, *success, inbound.timestamp);
+    entry.exit(&crate::ID)
+}
+
+pub fn outbound_message_address(dst_eid: u32, nonce: u64) -> Pubkey {
+    pda(&[OUTBOUND_MESSAGE_SEED, &dst_eid.to_be_bytes(), &nonce.to_be_bytes()])
+}
+
+/// The mirror, stats or feed accounts a report updates.
+pub fn accounts(src_eid: u32, message: &YieldMessage) -> Vec<LzAccount> {
+    let mirror = |user: &[u8; 32], protocol_id: &u64| {
+        pda(&[
+            POSITION_MIRROR_SEED,
+            &src_eid.to_be_bytes(),
+            user.as_ref(),
+            &protocol_id.to_be_bytes(),
+        ])
+    };
+    let stats = |protocol_id: &u64| {
+        pda(&[PROTOCOL_STATS_SEED, &src_eid.to_be_bytes(), &protocol_id.to_be_bytes()])
+    };
+    let keys = match message {
+        YieldMessage::PositionSync { user, protocol_id, .. } => vec![mirror(user, protocol_id)],
+        YieldMessage::YieldDistribution { protocol_id, .. }
+        | YieldMessage::FeeCollection { protocol_id, .. } => vec![stats(protocol_id)],
+        YieldMessage::LiquidationNotice { user, protocol_id, .. } => {
+            vec![mirror(user, protocol_id), stats(protocol_id)]
+        }
+        YieldMessage::PriceUpdate { token_mint, .. } => {
+            vec![pda(&[PRICE_FEED_SEED, token_mint.as_ref()])]
+        }
+        YieldMessage::MessageAck { nonce, .. } => vec![outbound_message_address(src_eid, *nonce)],
+        _ => vec![],
+    };
+    keys.into_iter().map(writable).collect()
+}
+
+fn load_mirror<'info>(
+    inbound: &mut Inbound<'info>,
+    user: [u8; 32],
+    protocol_id: u64,
+) -> Result<Account<'info, PositionMirror>> {
+    let src_eid = inbound.src_eid;
+    inbound.load_or_init(
+        &[
+            POSITION_MIRROR_SEED,
+            &src_eid.to_be_bytes(),
+            user.as_ref(),
+            &protocol_id.to_be_bytes(),
+        ],
+        |bump| PositionMirror {
+            src_eid,
+            user,
+            protocol_id,
+            principal: 0,
+            yield_earned: 0,
+            shares: 0,
+            position_health: 0,
+            liquidated_amount: 0,
+            last_compound_time: 0,
+            sync_timestamp: 0,
+            bump,
+        },
+    )
+}
+
+fn load_stats<'info>(inbound: &mut Inbound<'info>, protocol_id: u64) -> Result<Account<'info, ProtocolStats>> {
+    let src_eid = inbound.src_eid;
+    inbound.load_or_init(
+        &[PROTOCOL_STATS_SEED, &src_eid.to_be_bytes(), &protocol_id.to_be_bytes()],
+        |bump| ProtocolStats {
+            src_eid,
+            protocol_id,
+            total_yield_distributed: 0,
+            last_distribution_rate: 0,
+            last_distribution: 0,
+            fees_collected: [0; 5],
+            total_liquidated: 0,
+            total_liquidation_penalty: 0,
+            bump,
+        },
+    )
+}
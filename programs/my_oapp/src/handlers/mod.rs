@@ -0,0 +1,406 @@
+//! Handlers that execute decoded `YieldMessage`s for `lz_receive` and `lz_compose`. Each
+//! handler reads its accounts from the remaining accounts that follow the endpoint accounts,
+//! in the order its `*_accounts` function lists them for `lz_receive_types` and
+//! `lz_compose_types`. Every list starts with the accounts of `inbound_accounts`.
+
+pub mod guardian;
+pub mod ledger;
+pub mod protocol;
+pub mod vault;
+
+use crate::errors::MyOAppError;
+use crate::msg_codec::{
+    is_privileged_message, CrossChainMessage, MsgCodecError, YieldMessage, WITHDRAWAL_RELEASE_TYPE,
+};
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_lang::{system_program, Space};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+use oapp::endpoint_cpi::LzAccount;
+
+pub struct Inbound<'info> {
+    pub src_eid: u32,
+    pub timestamp: i64,
+    pub payer: &'info AccountInfo<'info>,
+    pub system_program: &'info AccountInfo<'info>,
+    accounts: std::slice::Iter<'info, AccountInfo<'info>>,
+    guardian_verified: bool, // Set by guardian::authenticate once the signature checks out
+}
+
+impl<'info> Inbound<'info> {
+    pub fn new(src_eid: u32, accounts: &'info [AccountInfo<'info>]) -> Result<Self> {
+        let mut accounts = accounts.iter();
+        let payer = next_account_info(&mut accounts)?;
+        let system_program = next_account_info(&mut accounts)?;
+        require_keys_eq!(system_program.key(), system_program::ID, MyOAppError::InvalidAccount);
+        Ok(Self {
+            src_eid,
+            timestamp: Clock::get()?.unix_timestamp,
+            payer,
+            system_program,
+            accounts,
+            guardian_verified: false,
+        })
+    }
+
+    pub fn next(&mut self) -> Result<&'info AccountInfo<'info>> {
+        Ok(next_account_info(&mut self.accounts)?)
+    }
+
+    /// Next account, which must be the PDA for `seeds` and already exist.
+    pub fn load<T>(&mut self, seeds: &[&[u8]]) -> Result<Account<'info, T>>
+    where
+        T: AccountSerialize + AccountDeserialize + Owner + Clone,
+    {
+        let info = self.next()?;
+        let (expected, _) = Pubkey::find_program_address(seeds, &crate::ID);
+        require_keys_eq!(info.key(), expected, MyOAppError::InvalidAccount);
+        Account::try_from(info)
+    }
+
+    /// Next account, which must be the PDA for `seeds`. Created and filled by `init` on first use.
+    pub fn load_or_init<T>(
+        &mut self,
+        seeds: &[&[u8]],
+        init: impl FnOnce(u8) -> T,
+    ) -> Result<Account<'info, T>>
+    where
+        T: AccountSerialize + AccountDeserialize + Owner + Clone + Space,
+    {
+        let info = self.next()?;
+        let (expected, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+        require_keys_eq!(info.key(), expected, MyOAppError::InvalidAccount);
+
+        if info.data_is_empty() {
+            let space = 8 + T::INIT_SPACE;
+            let bump_seed = [bump];
+            let mut signer_seeds = seeds.to_vec();
+            signer_seeds.push(&bump_seed);
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    self.system_program.clone(),
+                    system_program::CreateAccount {
+                        from: self.payer.clone(),
+                        to: info.clone(),
+                    },
+                    &[&signer_seeds],
+                ),
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                &crate::ID,
+            )?;
+            let mut data = info.try_borrow_mut_data()?;
+            init(bump).try_serialize(&mut &mut data[..])?;
+        }
+
+        Account::try_from(info)
+    }
+
+    /// Next account, which must be the PDA for `seeds`, or `None` if it doesn't exist.
+    pub fn load_if_exists<T>(&mut self, seeds: &[&[u8]]) -> Result<Option<Account<'info, T>>>
+    where
+        T: AccountSerialize + AccountDeserialize + Owner + Clone,
+    {
+        let info = self.next()?;
+        let (expected, _) = Pubkey::find_program_address(seeds, &crate::ID);
+        require_keys_eq!(info.key(), expected, MyOAppError::InvalidAccount);
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        Account::try_from(info).map(Some)
+    }
+
+    pub fn registry(&mut self) -> Result<Account<'info, ProtocolRegistry>> {
+        self.load(&[PROTOCOL_REGISTRY_SEED])
+    }
+
+    pub fn remote_authority(&mut self) -> Result<ProgramSigner<'info>> {
+        self.program_signer(REMOTE_AUTHORITY_SEED)
+    }
+
+    /// The aggregator only takes admin instructions from the remote admin, so it is only lent
+    /// to a message whose guardian signature was verified.
+    pub fn remote_admin(&mut self) -> Result<ProgramSigner<'info>> {
+        require!(self.guardian_verified, MyOAppError::MissingGuardianSignature);
+        self.program_signer(REMOTE_ADMIN_SEED)
+    }
+
+    fn program_signer(&mut self, seed: &'static [u8]) -> Result<ProgramSigner<'info>> {
+        let info = self.next()?;
+        let (expected, bump) = Pubkey::find_program_address(&[seed], &crate::ID);
+        require_keys_eq!(info.key(), expected, MyOAppError::InvalidAccount);
+        Ok(ProgramSigner { info, seed, bump })
+    }
+
+    pub fn yield_aggregator_program(&mut self) -> Result<&'info AccountInfo<'info>> {
+        let info = self.next()?;
+        require_keys_eq!(info.key(), ::yield_aggregator::ID, MyOAppError::InvalidAccount);
+        Ok(info)
+    }
+}
+
+/// System-owned PDA that signs for the OApp on the yield aggregator. The remote authority
+/// holds the liquidity backing remote users and deposits into vaults like any user. The
+/// remote admin is the aggregator's remote admin for rate, config, fee and pause changes.
+pub struct ProgramSigner<'info> {
+    pub info: &'info AccountInfo<'info>,
+    pub seed: &'static [u8],
+    pub bump: u8,
+}
+
+impl ProgramSigner<'_> {
+    pub fn seeds(&self) -> [&[u8]; 2] {
+        [self.seed, std::slice::from_ref(&self.bump)]
+    }
+}
+
+/// Token balance of a token account, or zero if it doesn't exist yet.
+pub fn token_amount(info: &AccountInfo) -> Result<u64> {
+    if info.data_is_empty() {
+        return Ok(0);
+    }
+    let data = info.try_borrow_data()?;
+    Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+}
+
+/// Looks up the aggregator protocol a message refers to.
+pub fn route(registry: &ProtocolRegistry, protocol_id: u64) -> Result<ProtocolRoute> {
+    registry
+        .route(protocol_id)
+        .cloned()
+        .ok_or_else(|| MyOAppError::InvalidProtocol.into())
+}
+
+/// Creates the replay records for this delivery, failing if either already exists. One is keyed
+/// by the packet guid and one by the message id, so the same message resent by the peer as a
+/// new packet is rejected too.
+pub fn record_delivery<'info>(
+    inbound: &mut Inbound<'info>,
+    guid: [u8; 32],
+    message: &CrossChainMessage,
+) -> Result<()> {
+    let src_eid = inbound.src_eid;
+    let message_id = message.header.message_id;
+    for seeds in [
+        [RECEIVED_MESSAGE_SEED, &src_eid.to_be_bytes(), &guid],
+        [RECEIVED_MESSAGE_ID_SEED, &src_eid.to_be_bytes(), &message_id],
+    ] {
+        let received_at = inbound.timestamp;
+        let mut created = false;
+        let record = inbound.load_or_init(&seeds, |bump| {
+            created = true;
+            ReceivedMessage {
+                src_eid,
+                guid,
+                message_id,
+                nonce: message.nonce,
+                timestamp: message.timestamp,
+                received_at,
+                bump,
+            }
+        })?;
+        require!(created, MyOAppError::MessageAlreadyProcessed);
+        record.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+/// Routes a validated message to its handler.
+pub fn dispatch<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    match message {
+        YieldMessage::DepositRequest {
+            user,
+            amount,
+            token_mint,
+            target_protocol_id,
+            min_apy,
+            ..
+        } => vault::deposit(inbound, *user, *amount, *token_mint, *target_protocol_id, *min_apy),
+        YieldMessage::WithdrawRequest { .. } => vault::withdraw(inbound, message),
+        YieldMessage::RebalanceRequest {
+            user,
+            from_protocol_id,
+            to_protocol_id,
+            amount,
+            ..
+        } => vault::rebalance(inbound, *user, *from_protocol_id, *to_protocol_id, *amount),
+        YieldMessage::YieldUpdate { protocol_id, new_apy, .. } => {
+            protocol::yield_update(inbound, *protocol_id, *new_apy)
+        }
+        YieldMessage::EmergencyAction { action_type, protocol_id, .. } => {
+            protocol::emergency_action(inbound, action_type, *protocol_id)
+        }
+        YieldMessage::ProtocolConfig {
+            protocol_id,
+            config_type,
+            config_data,
+            effective_timestamp,
+        } => protocol::protocol_config(
+            inbound,
+            *protocol_id,
+            config_type,
+            config_data,
+            *effective_timestamp,
+        ),
+        YieldMessage::PositionSync { .. } => ledger::position_sync(inbound, message),
+        YieldMessage::YieldDistribution { .. } => ledger::yield_distribution(inbound, message),
+        YieldMessage::GovernanceVote { .. } => ledger::governance_vote(inbound, message),
+        YieldMessage::LiquidationNotice { .. } => ledger::liquidation_notice(inbound, message),
+        YieldMessage::FeeCollection { .. } => ledger::fee_collection(inbound, message),
+        YieldMessage::PriceUpdate { .. } => ledger::price_update(inbound, message),
+        YieldMessage::MessageAck { .. } => ledger::message_ack(inbound, message),
+        YieldMessage::WithdrawalRelease { .. } => vault::withdrawal_release(inbound, message),
+    }
+}
+
+/// Accounts `Inbound::new` reads: the payer and the system program, then for a delivery the
+/// two `ReceivedMessage` records that `record_delivery` creates, given the source eid, the
+/// packet guid and the message id.
+pub fn inbound_accounts(delivery: Option<(u32, &[u8; 32], &[u8; 32])>) -> Vec<LzAccount> {
+    // The executor substitutes its own key for a default signer and pays for new accounts
+    let mut accounts = vec![
+        LzAccount { pubkey: Pubkey::default(), is_signer: true, is_writable: true },
+        readonly(system_program::ID),
+    ];
+    if let Some((src_eid, guid, message_id)) = delivery {
+        accounts.push(writable(pda(&[RECEIVED_MESSAGE_SEED, &src_eid.to_be_bytes(), guid])));
+        accounts.push(writable(pda(&[
+            RECEIVED_MESSAGE_ID_SEED,
+            &src_eid.to_be_bytes(),
+            message_id,
+        ])));
+    }
+    accounts
+}
+
+/// Accounts `guardian::authenticate` and `dispatch` read for `message`, after the inbound
+/// accounts.
+pub fn accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    message: &YieldMessage,
+) -> Result<Vec<LzAccount>> {
+    let mut accounts = if is_privileged_message(message.get_message_type()) {
+        guardian::accounts()
+    } else {
+        Vec::new()
+    };
+    accounts.extend(match message {
+        YieldMessage::DepositRequest { user, target_protocol_id, .. } => {
+            vault::deposit_accounts(registry, src_eid, *user, *target_protocol_id)?
+        }
+        YieldMessage::WithdrawRequest {
+            user,
+            protocol_id,
+            target_chain_id,
+            destination_address,
+            ..
+        } => vault::withdraw_accounts(
+            registry,
+            src_eid,
+            *user,
+            *protocol_id,
+            *target_chain_id,
+            destination_address,
+        )?,
+        YieldMessage::RebalanceRequest { user, from_protocol_id, to_protocol_id, .. } => {
+            vault::rebalance_accounts(
+                registry,
+                src_eid,
+                *user,
+                *from_protocol_id,
+                *to_protocol_id,
+            )?
+        }
+        YieldMessage::YieldUpdate { protocol_id, .. } => {
+            protocol::admin_accounts(registry, Some(*protocol_id))?
+        }
+        YieldMessage::EmergencyAction { action_type, protocol_id, .. } => {
+            protocol::emergency_action_accounts(registry, action_type, *protocol_id)?
+        }
+        YieldMessage::ProtocolConfig { protocol_id, .. } => {
+            protocol::admin_accounts(registry, Some(*protocol_id))?
+        }
+        YieldMessage::GovernanceVote { proposal_id, voter, .. } => {
+            ledger::governance_vote_accounts(registry, src_eid, *proposal_id, *voter)
+        }
+        YieldMessage::PositionSync { .. }
+        | YieldMessage::YieldDistribution { .. }
+        | YieldMessage::LiquidationNotice { .. }
+        | YieldMessage::FeeCollection { .. }
+        | YieldMessage::PriceUpdate { .. }
+        | YieldMessage::MessageAck { .. } => ledger::accounts(src_eid, message),
+        YieldMessage::WithdrawalRelease { protocol_id, destination_address, .. } => {
+            vault::withdrawal_release_accounts(registry, src_eid, *protocol_id, destination_address)?
+        }
+    });
+    Ok(accounts)
+}
+
+pub fn writable(pubkey: Pubkey) -> LzAccount {
+    LzAccount { pubkey, is_signer: false, is_writable: true }
+}
+
+pub fn readonly(pubkey: Pubkey) -> LzAccount {
+    LzAccount { pubkey, is_signer: false, is_writable: false }
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+pub fn registry_address() -> Pubkey {
+    pda(&[PROTOCOL_REGISTRY_SEED])
+}
+
+pub fn remote_authority_address() -> Pubkey {
+    pda(&[REMOTE_AUTHORITY_SEED])
+}
+
+pub fn remote_admin_address() -> Pubkey {
+    pda(&[REMOTE_ADMIN_SEED])
+}
+
+/// Aggregator accounts of a routed protocol, derived the same way the aggregator checks them.
+pub struct ProtocolKeys {
+    pub protocol_info: Pubkey,
+    pub yield_vault: Pubkey,
+    pub vault_token_account: Pubkey,
+    pub receipt_mint: Pubkey,
+}
+
+impl ProtocolKeys {
+    pub fn new(route: &ProtocolRoute) -> Self {
+        let program = ::yield_aggregator::ID;
+        let (protocol_info, _) = Pubkey::find_program_address(
+            &[::yield_aggregator::PROTOCOL_SEED, route.name.as_bytes()],
+            &program,
+        );
+        let (yield_vault, _) = Pubkey::find_program_address(
+            &[::yield_aggregator::YIELD_VAULT_SEED, protocol_info.as_ref()],
+            &program,
+        );
+        let (receipt_mint, _) = Pubkey::find_program_address(
+            &[::yield_aggregator::RECEIPT_MINT_SEED, yield_vault.as_ref()],
+            &program,
+        );
+        Self {
+            protocol_info,
+            yield_vault,
+            vault_token_account: get_associated_token_address(&yield_vault, &route.mint),
+            receipt_mint,
+        }
+    }
+}
+
+pub fn yield_aggregator_address() -> Pubkey {
+    Pubkey::find_program_address(&[::yield_aggregator::YIELD_AGGREGATOR_SEED], &::yield_aggregator::ID).0
+}
+
+/// Rejects message types this program doesn't know before the full payload is decoded.
+pub fn check_message_type(msg_type: u8) -> Result<()> {
+    require!((1..=WITHDRAWAL_RELEASE_TYPE).contains(&msg_type), MsgCodecError::UnsupportedMessageType);
+    Ok(())
+}
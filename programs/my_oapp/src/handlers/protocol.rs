@@ -0,0 +1,251 @@
+use super::{
+    readonly, registry_address, remote_admin_address, route, writable, yield_aggregator_address,
+    Inbound, ProgramSigner, ProtocolKeys,
+};
+use crate::errors::MyOAppError;
+use crate::msg_codec::{EmergencyActionType, MsgCodecError, ProtocolConfigType};
+use crate::ProtocolRegistry;
+use ::yield_aggregator::cpi::{self as aggregator_cpi, accounts as aggregator_accounts};
+use ::yield_aggregator::fees::FeeType;
+use ::yield_aggregator::instructions::{
+    EmergencyPauseParams, SetFeeParams, UpdateProtocolConfigParams, UpdateYieldRatesParams,
+};
+use anchor_lang::prelude::*;
+use oapp::endpoint_cpi::LzAccount;
+
+/// Accounts shared by every protocol handler, after the payer and system program.
+struct AdminAccounts<'info> {
+    authority: ProgramSigner<'info>,
+    program: &'info AccountInfo<'info>,
+    yield_aggregator: &'info AccountInfo<'info>,
+    system_program: &'info AccountInfo<'info>,
+}
+
+impl<'info> AdminAccounts<'info> {
+    fn load(inbound: &mut Inbound<'info>) -> Result<Self> {
+        Ok(Self {
+            authority: inbound.remote_admin()?,
+            program: inbound.yield_aggregator_program()?,
+            yield_aggregator: inbound.next()?,
+            system_program: inbound.system_program,
+        })
+    }
+
+    fn update_yield_rates(
+        &self,
+        protocol_info: &AccountInfo<'info>,
+        protocol_name: String,
+        new_apy: u64,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] = &self.authority.seeds();
+        aggregator_cpi::update_yield_rates(
+            CpiContext::new_with_signer(
+                self.program.clone(),
+                aggregator_accounts::UpdateYieldRates {
+                    protocol_info: protocol_info.clone(),
+                    yield_aggregator: self.yield_aggregator.clone(),
+                    admin: self.authority.info.clone(),
+                    system_program: self.system_program.clone(),
+                },
+                &[seeds],
+            ),
+            UpdateYieldRatesParams { protocol_name, new_apy },
+        )
+    }
+
+    fn update_protocol_config(
+        &self,
+        protocol_info: &AccountInfo<'info>,
+        params: UpdateProtocolConfigParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] = &self.authority.seeds();
+        aggregator_cpi::update_protocol_config(
+            CpiContext::new_with_signer(
+                self.program.clone(),
+                aggregator_accounts::UpdateProtocolConfig {
+                    protocol_info: protocol_info.clone(),
+                    yield_aggregator: self.yield_aggregator.clone(),
+                    admin: self.authority.info.clone(),
+                },
+                &[seeds],
+            ),
+            params,
+        )
+    }
+
+    fn emergency_pause(&self, pause: bool) -> Result<()> {
+        let seeds: &[&[u8]] = &self.authority.seeds();
+        aggregator_cpi::emergency_pause(
+            CpiContext::new_with_signer(
+                self.program.clone(),
+                aggregator_accounts::EmergencyPause {
+                    yield_aggregator: self.yield_aggregator.clone(),
+                    admin: self.authority.info.clone(),
+                    system_program: self.system_program.clone(),
+                },
+                &[seeds],
+            ),
+            EmergencyPauseParams { pause },
+        )
+    }
+
+    fn set_fee(&self, fee_type: FeeType, bps: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &self.authority.seeds();
+        aggregator_cpi::set_fee(
+            CpiContext::new_with_signer(
+                self.program.clone(),
+                aggregator_accounts::SetFee {
+                    yield_aggregator: self.yield_aggregator.clone(),
+                    admin: self.authority.info.clone(),
+                },
+                &[seeds],
+            ),
+            SetFeeParams { fee_type, bps },
+        )
+    }
+}
+
+fn config(protocol_name: String) -> UpdateProtocolConfigParams {
+    UpdateProtocolConfigParams {
+        protocol_name,
+        max_capacity: None,
+        risk_score: None,
+        is_active: None,
+    }
+}
+
+/// Big-endian like the rest of the wire format, so an EVM sender can build it with
+/// `abi.encodePacked(uint64(value))`.
+fn read_u64(data: &[u8]) -> Result<u64> {
+    let bytes: [u8; 8] = data.try_into().map_err(|_| MsgCodecError::InvalidPayload)?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Registry, remote admin, aggregator program and aggregator state, then the protocol account
+/// when the message targets one protocol.
+pub fn admin_accounts(
+    registry: &ProtocolRegistry,
+    protocol_id: Option<u64>,
+) -> Result<Vec<LzAccount>> {
+    let mut accounts = vec![
+        readonly(registry_address()),
+        writable(remote_admin_address()),
+        readonly(::yield_aggregator::ID),
+        writable(yield_aggregator_address()),
+    ];
+    if let Some(protocol_id) = protocol_id {
+        let route = route(registry, protocol_id)?;
+        accounts.push(writable(ProtocolKeys::new(&route).protocol_info));
+    }
+    Ok(accounts)
+}
+
+pub fn yield_update<'info>(inbound: &mut Inbound<'info>, protocol_id: u64, new_apy: u64) -> Result<()> {
+    let registry = inbound.registry()?;
+    let route = route(&registry, protocol_id)?;
+    let admin = AdminAccounts::load(inbound)?;
+    let protocol_info = inbound.next()?;
+    admin.update_yield_rates(protocol_info, route.name, new_apy)
+}
+
+/// Pause and unpause are global. Halting or resuming deposits targets one protocol when
+/// `protocol_id` is set and the whole aggregator otherwise.
+pub fn emergency_action<'info>(
+    inbound: &mut Inbound<'info>,
+    action_type: &EmergencyActionType,
+    protocol_id: Option<u64>,
+) -> Result<()> {
+    let registry = inbound.registry()?;
+    let admin = AdminAccounts::load(inbound)?;
+    match (action_type, protocol_id) {
+        (EmergencyActionType::Pause, _) => admin.emergency_pause(true),
+        (EmergencyActionType::Unpause, _) => admin.emergency_pause(false),
+        (EmergencyActionType::HaltDeposits, None) => admin.emergency_pause(true),
+        (EmergencyActionType::ResumeDeposits, None) => admin.emergency_pause(false),
+        (EmergencyActionType::HaltDeposits | EmergencyActionType::ResumeDeposits, Some(id)) => {
+            let route = route(&registry, id)?;
+            let protocol_info = inbound.next()?;
+            admin.update_protocol_config(
+                protocol_info,
+                UpdateProtocolConfigParams {
+                    is_active: Some(*action_type == EmergencyActionType::ResumeDeposits),
+                    ..config(route.name)
+                },
+            )
+        }
+        (EmergencyActionType::ForceWithdraw, _) => err!(MyOAppError::UnsupportedAction),
+    }
+}
+
+pub fn emergency_action_accounts(
+    registry: &ProtocolRegistry,
+    action_type: &EmergencyActionType,
+    protocol_id: Option<u64>,
+) -> Result<Vec<LzAccount>> {
+    match action_type {
+        EmergencyActionType::HaltDeposits | EmergencyActionType::ResumeDeposits => {
+            admin_accounts(registry, protocol_id)
+        }
+        _ => admin_accounts(registry, None),
+    }
+}
+
+/// `config_data` is a big-endian u64 for rates and capacity, a single byte for the risk
+/// score and a fee type byte followed by a big-endian u64 for fees. A config that isn't effective yet
+/// fails so the executor retries it later.
+pub fn protocol_config<'info>(
+    inbound: &mut Inbound<'info>,
+    protocol_id: u64,
+    config_type: &ProtocolConfigType,
+    config_data: &[u8],
+    effective_timestamp: i64,
+) -> Result<()> {
+    require!(effective_timestamp <= inbound.timestamp, MyOAppError::ConfigNotEffective);
+    let registry = inbound.registry()?;
+    let route = route(&registry, protocol_id)?;
+    let admin = AdminAccounts::load(inbound)?;
+    let protocol_info = inbound.next()?;
+
+    match config_type {
+        ProtocolConfigType::YieldRate => {
+            admin.update_yield_rates(protocol_info, route.name, read_u64(config_data)?)
+        }
+        ProtocolConfigType::CapacityLimits => admin.update_protocol_config(
+            protocol_info,
+            UpdateProtocolConfigParams {
+                max_capacity: Some(read_u64(config_data)?),
+                ..config(route.name)
+            },
+        ),
+        ProtocolConfigType::RiskParameters => {
+            require!(config_data.len() == 1, MsgCodecError::InvalidPayload);
+            admin.update_protocol_config(
+                protocol_info,
+                UpdateProtocolConfigParams {
+                    risk_score: Some(config_data[0]),
+                    ..config(route.name)
+                },
+            )
+        }
+        ProtocolConfigType::FeeStructure => {
+            require!(config_data.len() == 9, MsgCodecError::InvalidPayload);
+            let fee_type = FeeType::try_from_slice(&config_data[..1])
+                .map_err(|_| MsgCodecError::InvalidPayload)?;
+            admin.set_fee(fee_type, read_u64(&config_data[1..])?)
+        }
+        ProtocolConfigType::RebalanceThresholds => err!(MyOAppError::UnsupportedAction),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_data_is_big_endian() {
+        // abi.encodePacked(uint64(1_500))
+        assert_eq!(read_u64(&[0, 0, 0, 0, 0, 0, 0x05, 0xdc]).unwrap(), 1_500);
+        assert!(read_u64(&[0x05, 0xdc]).is_err());
+        assert!(read_u64(&[0; 9]).is_err());
+    }
+}
@@ -0,0 +1,624 @@
+use super::{
+    readonly, registry_address, remote_authority_address, route, token_amount, writable,
+    yield_aggregator_address, Inbound, ProgramSigner, ProtocolKeys,
+};
+use crate::errors::MyOAppError;
+use crate::events::WithdrawalReturnRequested;
+use crate::msg_codec::{MsgCodecError, YieldMessage, MAX_DESTINATION_ADDRESS_LEN};
+use crate::*;
+use ::yield_aggregator::{USER_POSITION_SEED, USER_SUMMARY_SEED};
+use ::yield_aggregator::cpi::{self as aggregator_cpi, accounts as aggregator_accounts};
+use ::yield_aggregator::instructions::{
+    DepositForYieldParams, RebalancePositionParams, WithdrawYieldParams,
+};
+use ::yield_aggregator::state::YieldVault;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use oapp::endpoint::{state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID};
+use oapp::endpoint_cpi::LzAccount;
+
+// Struct literals below evaluate their fields in the order written, which is the account order
+// lz_receive_types emits for each message.
+
+/// Deposits liquidity held by the remote authority into the target vault and credits the
+/// resulting shares to the remote user's position. The amount is drawn from the source
+/// chain's credit line.
+pub fn deposit<'info>(
+    inbound: &mut Inbound<'info>,
+    user: [u8; 32],
+    amount: u64,
+    token_mint: Pubkey,
+    protocol_id: u64,
+    min_apy: u64,
+) -> Result<()> {
+    let registry = inbound.registry()?;
+    let route = route(&registry, protocol_id)?;
+    require_keys_eq!(route.mint, token_mint, MyOAppError::InvalidProtocol);
+    let mut credit: Account<PeerCredit> =
+        inbound.load(&[PEER_CREDIT_SEED, &inbound.src_eid.to_be_bytes()])?;
+    credit.draw(amount).ok_or(MyOAppError::CreditLineExceeded)?;
+    let authority = inbound.remote_authority()?;
+    let program = inbound.yield_aggregator_program()?;
+    let timestamp = inbound.timestamp;
+    let (owner, linked) = resolve_owner(inbound, user)?;
+    let mut position = inbound.load_or_init(
+        &[
+            REMOTE_POSITION_SEED,
+            &owner.eid.to_be_bytes(),
+            &owner.address,
+            &protocol_id.to_be_bytes(),
+        ],
+        |bump| RemotePosition {
+            owner,
+            linked,
+            protocol_id,
+            shares: 0,
+            principal: 0,
+            pending_withdrawal: 0,
+            last_activity: timestamp,
+            bump,
+        },
+    )?;
+    position.linked = linked;
+
+    let accounts = aggregator_accounts::DepositForYield {
+        user_position: inbound.next()?.clone(),
+        user_summary: inbound.next()?.clone(),
+        protocol_info: inbound.next()?.clone(),
+        yield_vault: inbound.next()?.clone(),
+        vault_token_account: inbound.next()?.clone(),
+        user_token_account: inbound.next()?.clone(),
+        user_receipt_account: inbound.next()?.clone(),
+        mint: inbound.next()?.clone(),
+        receipt_mint: inbound.next()?.clone(),
+        yield_aggregator: inbound.next()?.clone(),
+        user: authority.info.clone(),
+        token_program: inbound.next()?.clone(),
+        associated_token_program: inbound.next()?.clone(),
+        system_program: inbound.system_program.clone(),
+    };
+    let receipt = accounts.user_receipt_account.clone();
+    let shares_before = token_amount(&receipt)?;
+
+    let seeds: &[&[u8]] = &authority.seeds();
+    aggregator_cpi::deposit_for_yield(
+        CpiContext::new_with_signer(program.clone(), accounts, &[seeds]),
+        DepositForYieldParams {
+            amount,
+            target_protocol: route.name,
+            target_chain_id: 0,
+            min_apy,
+        },
+    )?;
+
+    let minted = token_amount(&receipt)?
+        .checked_sub(shares_before)
+        .ok_or(MyOAppError::MathOverflow)?;
+    position.shares = position.shares.checked_add(minted).ok_or(MyOAppError::MathOverflow)?;
+    position.principal = position.principal.checked_add(amount).ok_or(MyOAppError::MathOverflow)?;
+    position.last_activity = timestamp;
+    credit.exit(&crate::ID)?;
+    position.exit(&crate::ID)
+}
+
+/// Redeems `amount` assets of the remote user's shares into remote custody. They are released
+/// to `destination_address` right away when it is on this chain, and otherwise wait in a
+/// `PendingWithdrawal` for `send_withdrawal_release` to send the return leg to `target_chain_id`.
+pub fn withdraw<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::WithdrawRequest {
+        user,
+        amount,
+        token_mint,
+        protocol_id,
+        target_chain_id,
+        destination_address,
+        withdraw_yield,
+        ..
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let registry = inbound.registry()?;
+    let route = route(&registry, *protocol_id)?;
+    require_keys_eq!(route.mint, *token_mint, MyOAppError::InvalidProtocol);
+    let mut credit: Account<PeerCredit> =
+        inbound.load(&[PEER_CREDIT_SEED, &inbound.src_eid.to_be_bytes()])?;
+    let authority = inbound.remote_authority()?;
+    let program = inbound.yield_aggregator_program()?;
+    // Only a user with a position here has anything to withdraw
+    let (owner, linked) = resolve_owner(inbound, *user)?;
+    let mut position: Account<RemotePosition> = inbound.load(&[
+        REMOTE_POSITION_SEED,
+        &owner.eid.to_be_bytes(),
+        &owner.address,
+        &protocol_id.to_be_bytes(),
+    ])?;
+
+    let accounts = aggregator_accounts::WithdrawYield {
+        user_position: inbound.next()?.clone(),
+        user_summary: inbound.next()?.clone(),
+        protocol_info: inbound.next()?.clone(),
+        yield_vault: inbound.next()?.clone(),
+        vault_token_account: inbound.next()?.clone(),
+        user_token_account: inbound.next()?.clone(),
+        user_receipt_account: inbound.next()?.clone(),
+        mint: inbound.next()?.clone(),
+        receipt_mint: inbound.next()?.clone(),
+        yield_aggregator: inbound.next()?.clone(),
+        user: authority.info.clone(),
+        token_program: inbound.next()?.clone(),
+        system_program: inbound.system_program.clone(),
+    };
+    // Taking only the yield leaves the principal in place, so it is bounded by what the shares
+    // are worth above it
+    if *withdraw_yield {
+        require_keys_eq!(
+            *accounts.yield_vault.owner,
+            ::yield_aggregator::ID,
+            MyOAppError::InvalidAccount
+        );
+        let vault = YieldVault::try_deserialize(&mut &accounts.yield_vault.try_borrow_data()?[..])?;
+        let earned = vault.preview_redeem(position.shares)?.saturating_sub(position.principal);
+        require!(*amount <= earned, MyOAppError::WithdrawalExceedsBalance);
+    }
+    let receipt = accounts.user_receipt_account.clone();
+    let custody = accounts.user_token_account.clone();
+    let mint = accounts.mint.clone();
+    let token_program = accounts.token_program.clone();
+    let shares_before = token_amount(&receipt)?;
+    let assets_before = token_amount(&custody)?;
+
+    let seeds: &[&[u8]] = &authority.seeds();
+    aggregator_cpi::withdraw_yield(
+        CpiContext::new_with_signer(program.clone(), accounts, &[seeds]),
+        WithdrawYieldParams {
+            amount: *amount,
+            protocol_name: route.name,
+            target_chain_id: 0,
+        },
+    )?;
+
+    // Custody shares are pooled, so the user's own share balance is what bounds the withdrawal
+    let shares = shares_before
+        .checked_sub(token_amount(&receipt)?)
+        .ok_or(MyOAppError::MathOverflow)?;
+    let principal = if *withdraw_yield {
+        position.debit_yield(shares).map(|_| 0)
+    } else {
+        position.debit(shares)
+    };
+    credit.repay(principal.ok_or(MyOAppError::WithdrawalExceedsBalance)?);
+    credit.exit(&crate::ID)?;
+    let released = token_amount(&custody)?
+        .checked_sub(assets_before)
+        .ok_or(MyOAppError::MathOverflow)?;
+    position.linked = linked;
+    position.last_activity = inbound.timestamp;
+
+    let endpoint = inbound.next()?;
+    require_keys_eq!(endpoint.key(), endpoint_settings_address(), MyOAppError::InvalidAccount);
+    let local_eid = Account::<EndpointSettings>::try_from(endpoint)?.eid;
+    if *target_chain_id == local_eid {
+        let destination = Pubkey::try_from(destination_address.as_slice())
+            .map_err(|_| MsgCodecError::InvalidPayload)?;
+        // A linked owner is paid out here only to its own account
+        if linked != Pubkey::default() {
+            require_keys_eq!(destination, linked, MyOAppError::Unauthorized);
+        }
+        inbound.next()?; // Pending withdrawal, only used for a return leg
+        pay_out(inbound, &authority, custody, mint, token_program, destination, released)?;
+    } else {
+        let position_key = position.key();
+        let timestamp = inbound.timestamp;
+        let mut pending = inbound.load_or_init(
+            &[
+                PENDING_WITHDRAWAL_SEED,
+                position_key.as_ref(),
+                &target_chain_id.to_be_bytes(),
+                destination_address,
+            ],
+            |bump| PendingWithdrawal {
+                position: position_key,
+                dst_eid: *target_chain_id,
+                destination_address: destination_address.clone(),
+                token_mint: *token_mint,
+                amount: 0,
+                requested_at: timestamp,
+                bump,
+            },
+        )?;
+        pending.amount = pending.amount.checked_add(released).ok_or(MyOAppError::MathOverflow)?;
+        pending.requested_at = timestamp;
+        pending.exit(&crate::ID)?;
+        position.pending_withdrawal = position
+            .pending_withdrawal
+            .checked_add(released)
+            .ok_or(MyOAppError::MathOverflow)?;
+        emit!(WithdrawalReturnRequested {
+            dst_eid: *target_chain_id,
+            user: *user,
+            protocol_id: *protocol_id,
+            amount: released,
+            destination_address: destination_address.clone(),
+            timestamp,
+        });
+    }
+    position.exit(&crate::ID)
+}
+
+/// Pays the return leg of a withdrawal redeemed on the source chain out of this chain's
+/// custody. It draws on the source chain's credit line like a deposit, since that chain now
+/// holds the redeemed assets.
+pub fn withdrawal_release<'info>(inbound: &mut Inbound<'info>, message: &YieldMessage) -> Result<()> {
+    let YieldMessage::WithdrawalRelease {
+        amount,
+        token_mint,
+        protocol_id,
+        destination_address,
+        ..
+    } = message
+    else {
+        return err!(MsgCodecError::UnsupportedMessageType);
+    };
+
+    let registry = inbound.registry()?;
+    let route = route(&registry, *protocol_id)?;
+    require_keys_eq!(route.mint, *token_mint, MyOAppError::InvalidProtocol);
+    let destination = Pubkey::try_from(destination_address.as_slice())
+        .map_err(|_| MsgCodecError::InvalidPayload)?;
+    let mut credit: Account<PeerCredit> =
+        inbound.load(&[PEER_CREDIT_SEED, &inbound.src_eid.to_be_bytes()])?;
+    credit.draw(*amount).ok_or(MyOAppError::CreditLineExceeded)?;
+    let authority = inbound.remote_authority()?;
+    let custody = inbound.next()?;
+    require_keys_eq!(
+        custody.key(),
+        get_associated_token_address(&authority.info.key(), token_mint),
+        MyOAppError::InvalidAccount
+    );
+    let mint = inbound.next()?;
+    require_keys_eq!(mint.key(), *token_mint, MyOAppError::InvalidAccount);
+    let token_program = inbound.next()?;
+    require_keys_eq!(token_program.key(), token::ID, MyOAppError::InvalidAccount);
+
+    pay_out(
+        inbound,
+        &authority,
+        custody.clone(),
+        mint.clone(),
+        token_program.clone(),
+        destination,
+        *amount,
+    )?;
+    credit.exit(&crate::ID)
+}
+
+/// Transfers `amount` from the remote authority's custody to the associated token account of
+/// `destination`, creating it if needed. Reads the destination, its token account and the ATA
+/// program.
+fn pay_out<'info>(
+    inbound: &mut Inbound<'info>,
+    authority: &ProgramSigner<'info>,
+    custody: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    destination: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let owner = inbound.next()?;
+    require_keys_eq!(owner.key(), destination, MyOAppError::InvalidAccount);
+    let destination_token_account = inbound.next()?;
+    let associated_token_program = inbound.next()?;
+    associated_token::create_idempotent(CpiContext::new(
+        associated_token_program.clone(),
+        associated_token::Create {
+            payer: inbound.payer.clone(),
+            associated_token: destination_token_account.clone(),
+            authority: owner.clone(),
+            mint,
+            system_program: inbound.system_program.clone(),
+            token_program: token_program.clone(),
+        },
+    ))?;
+    let seeds: &[&[u8]] = &authority.seeds();
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program,
+            token::Transfer {
+                from: custody,
+                to: destination_token_account.clone(),
+                authority: authority.info.clone(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+/// Moves `amount` assets of the remote user's position between two local vaults.
+pub fn rebalance<'info>(
+    inbound: &mut Inbound<'info>,
+    user: [u8; 32],
+    from_protocol_id: u64,
+    to_protocol_id: u64,
+    amount: u64,
+) -> Result<()> {
+    let registry = inbound.registry()?;
+    let from_route = route(&registry, from_protocol_id)?;
+    let to_route = route(&registry, to_protocol_id)?;
+    let authority = inbound.remote_authority()?;
+    let program = inbound.yield_aggregator_program()?;
+    let timestamp = inbound.timestamp;
+    let (owner, linked) = resolve_owner(inbound, user)?;
+    let mut from_position: Account<RemotePosition> = inbound.load(&[
+        REMOTE_POSITION_SEED,
+        &owner.eid.to_be_bytes(),
+        &owner.address,
+        &from_protocol_id.to_be_bytes(),
+    ])?;
+    let mut to_position = inbound.load_or_init(
+        &[
+            REMOTE_POSITION_SEED,
+            &owner.eid.to_be_bytes(),
+            &owner.address,
+            &to_protocol_id.to_be_bytes(),
+        ],
+        |bump| RemotePosition {
+            owner,
+            linked,
+            protocol_id: to_protocol_id,
+            shares: 0,
+            principal: 0,
+            pending_withdrawal: 0,
+            last_activity: timestamp,
+            bump,
+        },
+    )?;
+
+    let accounts = aggregator_accounts::RebalancePosition {
+        from_position: inbound.next()?.clone(),
+        to_position: inbound.next()?.clone(),
+        user_summary: inbound.next()?.clone(),
+        from_protocol: inbound.next()?.clone(),
+        to_protocol: inbound.next()?.clone(),
+        from_vault: inbound.next()?.clone(),
+        to_vault: inbound.next()?.clone(),
+        from_vault_token_account: inbound.next()?.clone(),
+        to_vault_token_account: inbound.next()?.clone(),
+        user_from_receipt_account: inbound.next()?.clone(),
+        user_to_receipt_account: inbound.next()?.clone(),
+        mint: inbound.next()?.clone(),
+        from_receipt_mint: inbound.next()?.clone(),
+        to_receipt_mint: inbound.next()?.clone(),
+        yield_aggregator: inbound.next()?.clone(),
+        user: authority.info.clone(),
+        token_program: inbound.next()?.clone(),
+        associated_token_program: inbound.next()?.clone(),
+        system_program: inbound.system_program.clone(),
+    };
+    let from_receipt = accounts.user_from_receipt_account.clone();
+    let to_receipt = accounts.user_to_receipt_account.clone();
+    let from_before = token_amount(&from_receipt)?;
+    let to_before = token_amount(&to_receipt)?;
+
+    let seeds: &[&[u8]] = &authority.seeds();
+    aggregator_cpi::rebalance_position(
+        CpiContext::new_with_signer(program.clone(), accounts, &[seeds]),
+        RebalancePositionParams {
+            from_protocol: from_route.name,
+            to_protocol: to_route.name,
+            amount,
+            target_chain_id: 0,
+        },
+    )?;
+
+    let redeemed = from_before
+        .checked_sub(token_amount(&from_receipt)?)
+        .ok_or(MyOAppError::MathOverflow)?;
+    let principal = from_position
+        .debit(redeemed)
+        .ok_or(MyOAppError::WithdrawalExceedsBalance)?;
+    from_position.linked = linked;
+    from_position.last_activity = timestamp;
+    to_position.linked = linked;
+    let minted = token_amount(&to_receipt)?
+        .checked_sub(to_before)
+        .ok_or(MyOAppError::MathOverflow)?;
+    to_position.shares = to_position.shares.checked_add(minted).ok_or(MyOAppError::MathOverflow)?;
+    to_position.principal =
+        to_position.principal.checked_add(principal).ok_or(MyOAppError::MathOverflow)?;
+    to_position.last_activity = timestamp;
+
+    from_position.exit(&crate::ID)?;
+    to_position.exit(&crate::ID)
+}
+
+/// Positions of remote users are keyed by their universal address on the source chain.
+fn remote_position_address(src_eid: u32, user: [u8; 32], protocol_id: u64) -> Pubkey {
+    super::pda(&[
+        REMOTE_POSITION_SEED,
+        &src_eid.to_be_bytes(),
+        &user,
+        &protocol_id.to_be_bytes(),
+    ])
+}
+
+fn identity_link_address(src_eid: u32, user: [u8; 32]) -> Pubkey {
+    super::pda(&[IDENTITY_LINK_SEED, &src_eid.to_be_bytes(), &user])
+}
+
+/// The remote user as a universal address on the source chain, which keys its positions, and
+/// the Solana account it has linked with `link_identity`, default when there is none. Reads
+/// the `IdentityLink` account, which may not exist.
+fn resolve_owner(inbound: &mut Inbound, user: [u8; 32]) -> Result<(UniversalAddress, Pubkey)> {
+    let owner = UniversalAddress::new(inbound.src_eid, user);
+    let link: Option<Account<IdentityLink>> =
+        inbound.load_if_exists(&[IDENTITY_LINK_SEED, &owner.eid.to_be_bytes(), &owner.address])?;
+    Ok((owner, link.map(|link| link.solana).unwrap_or_default()))
+}
+
+fn endpoint_settings_address() -> Pubkey {
+    Pubkey::find_program_address(&[ENDPOINT_SEED], &ENDPOINT_ID).0
+}
+
+fn aggregator_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &::yield_aggregator::ID).0
+}
+
+/// Shared by deposits and withdrawals, which differ only in the trailing ATA program. The
+/// remote authority's custody account is its associated token account for the route mint.
+fn vault_accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    user: [u8; 32],
+    protocol_id: u64,
+) -> Result<Vec<LzAccount>> {
+    let route = route(registry, protocol_id)?;
+    let authority = remote_authority_address();
+    let protocol = ProtocolKeys::new(&route);
+    Ok(vec![
+        readonly(registry_address()),
+        writable(super::pda(&[PEER_CREDIT_SEED, &src_eid.to_be_bytes()])),
+        writable(authority),
+        readonly(::yield_aggregator::ID),
+        readonly(identity_link_address(src_eid, user)),
+        writable(remote_position_address(src_eid, user, protocol_id)),
+        writable(aggregator_pda(&[
+            USER_POSITION_SEED,
+            authority.as_ref(),
+            protocol.protocol_info.as_ref(),
+        ])),
+        writable(aggregator_pda(&[USER_SUMMARY_SEED, authority.as_ref()])),
+        writable(protocol.protocol_info),
+        writable(protocol.yield_vault),
+        writable(protocol.vault_token_account),
+        writable(get_associated_token_address(&authority, &route.mint)),
+        writable(get_associated_token_address(&authority, &protocol.receipt_mint)),
+        readonly(route.mint),
+        writable(protocol.receipt_mint),
+        writable(yield_aggregator_address()),
+        readonly(token::ID),
+    ])
+}
+
+pub fn deposit_accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    user: [u8; 32],
+    protocol_id: u64,
+) -> Result<Vec<LzAccount>> {
+    let mut accounts = vault_accounts(registry, src_eid, user, protocol_id)?;
+    accounts.push(readonly(associated_token::ID));
+    Ok(accounts)
+}
+
+/// The vault accounts, the endpoint settings for the local eid and the pending withdrawal for
+/// a return leg. A destination that can be a Solana address also gets its owner, token account
+/// and the ATA program, which are only read when the withdrawal is released on this chain.
+pub fn withdraw_accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    user: [u8; 32],
+    protocol_id: u64,
+    target_chain_id: u32,
+    destination_address: &[u8],
+) -> Result<Vec<LzAccount>> {
+    require!(
+        destination_address.len() <= MAX_DESTINATION_ADDRESS_LEN,
+        MsgCodecError::InvalidPayload
+    );
+    let mint = route(registry, protocol_id)?.mint;
+    let mut accounts = vault_accounts(registry, src_eid, user, protocol_id)?;
+    accounts.push(readonly(endpoint_settings_address()));
+    accounts.push(writable(super::pda(&[
+        PENDING_WITHDRAWAL_SEED,
+        remote_position_address(src_eid, user, protocol_id).as_ref(),
+        &target_chain_id.to_be_bytes(),
+        destination_address,
+    ])));
+    if let Ok(destination) = Pubkey::try_from(destination_address) {
+        accounts.extend(payout_accounts(destination, &mint));
+    }
+    Ok(accounts)
+}
+
+/// Registry, credit line, remote authority, custody account, mint and token program, then
+/// the payout accounts of the destination.
+pub fn withdrawal_release_accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    protocol_id: u64,
+    destination_address: &[u8],
+) -> Result<Vec<LzAccount>> {
+    let mint = route(registry, protocol_id)?.mint;
+    let destination = Pubkey::try_from(destination_address)
+        .map_err(|_| MsgCodecError::InvalidPayload)?;
+    let authority = remote_authority_address();
+    let mut accounts = vec![
+        readonly(registry_address()),
+        writable(super::pda(&[PEER_CREDIT_SEED, &src_eid.to_be_bytes()])),
+        readonly(authority),
+        writable(get_associated_token_address(&authority, &mint)),
+        readonly(mint),
+        readonly(token::ID),
+    ];
+    accounts.extend(payout_accounts(destination, &mint));
+    Ok(accounts)
+}
+
+fn payout_accounts(destination: Pubkey, mint: &Pubkey) -> [LzAccount; 3] {
+    [
+        readonly(destination),
+        writable(get_associated_token_address(&destination, mint)),
+        readonly(associated_token::ID),
+    ]
+}
+
+pub fn rebalance_accounts(
+    registry: &ProtocolRegistry,
+    src_eid: u32,
+    user: [u8; 32],
+    from_protocol_id: u64,
+    to_protocol_id: u64,
+) -> Result<Vec<LzAccount>> {
+    let from_route = route(registry, from_protocol_id)?;
+    let to_route = route(registry, to_protocol_id)?;
+    let authority = remote_authority_address();
+    let from = ProtocolKeys::new(&from_route);
+    let to = ProtocolKeys::new(&to_route);
+    Ok(vec![
+        readonly(registry_address()),
+        writable(authority),
+        readonly(::yield_aggregator::ID),
+        readonly(identity_link_address(src_eid, user)),
+        writable(remote_position_address(src_eid, user, from_protocol_id)),
+        writable(remote_position_address(src_eid, user, to_protocol_id)),
+        writable(aggregator_pda(&[
+            USER_POSITION_SEED,
+            authority.as_ref(),
+            from.protocol_info.as_ref(),
+        ])),
+        writable(aggregator_pda(&[
+            USER_POSITION_SEED,
+            authority.as_ref(),
+            to.protocol_info.as_ref(),
+        ])),
+        writable(aggregator_pda(&[USER_SUMMARY_SEED, authority.as_ref()])),
+        writable(from.protocol_info),
+        writable(to.protocol_info),
+        writable(from.yield_vault),
+        writable(to.yield_vault),
+        writable(from.vault_token_account),
+        writable(to.vault_token_account),
+        writable(get_associated_token_address(&authority, &from.receipt_mint)),
+        writable(get_associated_token_address(&authority, &to.receipt_mint)),
+        readonly(from_route.mint),
+        writable(from.receipt_mint),
+        writable(to.receipt_mint),
+        writable(yield_aggregator_address()),
+        readonly(token::ID),
+        readonly(associated_token::ID),
+    ])
+}
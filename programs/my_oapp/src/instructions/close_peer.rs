@@ -0,0 +1,37 @@
+use crate::errors::MyOAppError;
+use crate::events::PeerClosed;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: ClosePeerParams)]
+pub struct ClosePeer<'info> {
+    #[account(
+        mut,
+        close = admin,
+        seeds = [PEER_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+impl ClosePeer<'_> {
+    /// Stops sends to and receives from the pathway. Its nonce account stays, so `init_peer`
+    /// can reopen the pathway without resetting the inbound nonce.
+    pub fn apply(ctx: &mut Context<ClosePeer>, params: &ClosePeerParams) -> Result<()> {
+        emit!(PeerClosed {
+            eid: params.dst_eid,
+            peer_address: ctx.accounts.peer.peer_address,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ClosePeerParams {
+    pub dst_eid: u32,
+}
@@ -1,2 +1,53 @@
-// This file is left empty as the functionality is implemented in yield_aggregator/instructions.rs
-// This file exists only to satisfy the module declaration
\ No newline at end of file
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+use ::yield_aggregator::state::ProtocolInfo;
+use ::yield_aggregator::strategy::{rank, Strategy, MAX_STRATEGY_PROTOCOLS};
+
+/// Read-only ranking of yield-aggregator protocols. The candidate `ProtocolInfo` accounts are
+/// passed as remaining accounts and the allocation is returned through return data.
+#[derive(Accounts)]
+pub struct GetOptimalStrategy<'info> {
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+}
+
+impl<'info> GetOptimalStrategy<'info> {
+    pub fn apply(ctx: &Context<GetOptimalStrategy>, params: &GetOptimalStrategyParams) -> Result<Strategy> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_STRATEGY_PROTOCOLS,
+            MyOAppError::TooManyProtocols
+        );
+
+        let mut protocols: Vec<(Pubkey, ProtocolInfo)> =
+            Vec::with_capacity(ctx.remaining_accounts.len());
+        for account in ctx.remaining_accounts {
+            // Only accounts owned by the aggregator program can carry its ProtocolInfo data
+            require_keys_eq!(*account.owner, ::yield_aggregator::ID, MyOAppError::InvalidProtocol);
+            require!(
+                protocols.iter().all(|(key, _)| key != account.key),
+                MyOAppError::InvalidProtocol
+            );
+            let data = account.try_borrow_data()?;
+            let protocol = ProtocolInfo::try_deserialize(&mut &data[..])?;
+            protocols.push((account.key(), protocol));
+        }
+
+        let candidates: Vec<(Pubkey, &ProtocolInfo)> =
+            protocols.iter().map(|(key, protocol)| (*key, protocol)).collect();
+        Ok(rank(
+            &candidates,
+            params.amount,
+            params.risk_tolerance,
+            params.min_apy,
+            Clock::get()?.unix_timestamp,
+        ))
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct GetOptimalStrategyParams {
+    pub amount: u64,
+    pub risk_tolerance: u8, // Highest risk score (1-10) the user accepts
+    pub min_apy: u64,
+}
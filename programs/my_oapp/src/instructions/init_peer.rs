@@ -0,0 +1,62 @@
+use crate::errors::MyOAppError;
+use crate::events::PeerInitialized;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: InitPeerParams)]
+pub struct InitPeer<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [PEER_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump,
+        space = OAppPeerConfig::SIZE,
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [NONCE_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes(), &params.peer_address],
+        bump,
+        space = 8 + Nonce::INIT_SPACE,
+    )]
+    pub nonce_account: Account<'info, Nonce>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitPeer<'info> {
+    /// A nonce account left by a closed peer is reused, so the pathway keeps its inbound nonce.
+    pub fn apply(ctx: &mut Context<InitPeer>, params: &InitPeerParams) -> Result<()> {
+        params.enforced_options.validate()?;
+
+        let peer = &mut ctx.accounts.peer;
+        peer.peer_address = params.peer_address;
+        peer.bump = ctx.bumps.peer;
+        peer.enforced_options = params.enforced_options.clone();
+        peer.outbound_nonce = 0;
+
+        let nonce_account = &mut ctx.accounts.nonce_account;
+        nonce_account.ordered_nonce = params.ordered_nonce;
+        nonce_account.bump = ctx.bumps.nonce_account;
+
+        emit!(PeerInitialized {
+            eid: params.dst_eid,
+            peer_address: params.peer_address,
+            ordered_nonce: params.ordered_nonce,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct InitPeerParams {
+    pub dst_eid: u32,
+    pub peer_address: [u8; 32],
+    pub enforced_options: EnforcedOptions,
+    pub ordered_nonce: bool,
+}
@@ -1,5 +1,6 @@
 use crate::*;
 use anchor_lang::prelude::*;
+use oapp::endpoint::{ENDPOINT_SEED, ID as ENDPOINT_ID};
 
 #[derive(Accounts)]
 #[instruction(params: InitStoreParams)]
@@ -12,6 +13,22 @@ pub struct InitStore<'info> {
         space = OAppStore::SIZE,
     )]
     pub store: Account<'info, OAppStore>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LZ_RECEIVE_TYPES_SEED, &store.key().to_bytes()],
+        bump,
+        space = LzReceiveTypesAccounts::SIZE,
+    )]
+    pub lz_receive_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LZ_COMPOSE_TYPES_SEED, &store.key().to_bytes()],
+        bump,
+        space = LzComposeTypesAccounts::SIZE,
+    )]
+    pub lz_compose_types_accounts: Account<'info, LzComposeTypesAccounts>,
     #[account(mut)]
     pub admin: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -25,6 +42,16 @@ impl<'info> InitStore<'info> {
         store.bump = ctx.bumps.store;
         store.endpoint_program = params.endpoint_program;
         store.string = params.string.clone();
+
+        let types_accounts = &mut ctx.accounts.lz_receive_types_accounts;
+        types_accounts.store = store.key();
+        types_accounts.endpoint =
+            Pubkey::find_program_address(&[ENDPOINT_SEED], &ENDPOINT_ID).0;
+        types_accounts.registry = handlers::registry_address();
+
+        let compose_types_accounts = &mut ctx.accounts.lz_compose_types_accounts;
+        compose_types_accounts.store = store.key();
+        compose_types_accounts.registry = handlers::registry_address();
         Ok(())
     }
 }
@@ -0,0 +1,58 @@
+use crate::errors::MyOAppError;
+use crate::events::IdentityLinked;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: LinkIdentityParams)]
+pub struct LinkIdentity<'info> {
+    /// Solana account the address is linked to
+    #[account(mut)]
+    pub solana: Signer<'info>,
+    #[account(
+        init,
+        payer = solana,
+        seeds = [IDENTITY_LINK_SEED, &params.eid.to_be_bytes(), &params.address],
+        bump,
+        space = 8 + IdentityLink::INIT_SPACE,
+    )]
+    pub identity_link: Account<'info, IdentityLink>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LinkIdentity<'info> {
+    /// Links an EVM address on `eid` to the signer. Both sides agree to it: the signer here, the
+    /// EVM account by signing `IdentityLink::link_message` with `personal_sign`.
+    pub fn apply(ctx: &mut Context<LinkIdentity>, params: &LinkIdentityParams) -> Result<()> {
+        let universal = UniversalAddress::new(params.eid, params.address);
+        let solana = ctx.accounts.solana.key();
+        let signer = evm_signer(
+            &IdentityLink::link_message(&universal, &solana),
+            &params.signature,
+            params.recovery_id,
+        )
+        .ok_or(MyOAppError::InvalidIdentityProof)?;
+        require!(
+            UniversalAddress::from_evm(params.eid, signer) == universal,
+            MyOAppError::InvalidIdentityProof
+        );
+
+        ctx.accounts.identity_link.set_inner(IdentityLink {
+            universal,
+            solana,
+            linked_at: Clock::get()?.unix_timestamp,
+            bump: ctx.bumps.identity_link,
+        });
+
+        emit!(IdentityLinked { eid: params.eid, address: params.address, solana });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct LinkIdentityParams {
+    pub eid: u32,
+    pub address: [u8; 32], // Left-padded EVM address, see UniversalAddress
+    pub signature: [u8; 64],
+    pub recovery_id: u8,
+}
@@ -0,0 +1,88 @@
+use crate::errors::MyOAppError;
+use crate::events::PositionSyncRequested;
+use crate::msg_codec::{decode_forwarded, CrossChainMessage, YieldMessage, COMPOSE_REQUEST_SYNC};
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::{
+    endpoint::{
+        cpi::accounts::ClearCompose, instructions::ClearComposeParams, ConstructCPIContext,
+        ID as ENDPOINT_ID,
+    },
+    LzComposeParams,
+};
+
+#[derive(Accounts)]
+pub struct LzCompose<'info> {
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+}
+
+impl<'info> LzCompose<'info> {
+    /// Runs the payload of a message `lz_receive` queued for composing. Remaining accounts are
+    /// the endpoint clear_compose accounts followed by the accounts of the message handler,
+    /// in the order `lz_compose_types` returns them.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, LzCompose<'info>>,
+        params: &LzComposeParams,
+    ) -> Result<()> {
+        // Only messages this store forwarded to itself were checked by lz_receive
+        require_keys_eq!(params.from, ctx.accounts.store.key(), MyOAppError::Unauthorized);
+
+        let (src_eid, message) = decode_forwarded(&params.message)?;
+        let message = CrossChainMessage::decode(message)?;
+        message.validate_format()?;
+        message.payload.validate()?;
+
+        require!(
+            ctx.remaining_accounts.len() >= ClearCompose::MIN_ACCOUNTS_LEN,
+            ErrorCode::AccountNotEnoughKeys
+        );
+        let (clear_accounts, handler_accounts) =
+            ctx.remaining_accounts.split_at(ClearCompose::MIN_ACCOUNTS_LEN);
+
+        let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
+        oapp::endpoint_cpi::clear_compose(
+            ENDPOINT_ID,
+            ctx.accounts.store.key(),
+            clear_accounts,
+            seeds,
+            ClearComposeParams {
+                from: params.from,
+                guid: params.guid,
+                index: params.index,
+                message: params.message.clone(),
+            },
+        )?;
+
+        let mut inbound = handlers::Inbound::new(src_eid, handler_accounts)?;
+        handlers::guardian::authenticate(&mut inbound, &message)?;
+        handlers::dispatch(&mut inbound, &message.payload)?;
+
+        if message.compose_flags() & COMPOSE_REQUEST_SYNC != 0 {
+            if let Some((user, protocol_id)) = synced_position(&message.payload) {
+                emit!(PositionSyncRequested {
+                    dst_eid: src_eid,
+                    user,
+                    protocol_id,
+                    guid: params.guid,
+                    timestamp: inbound.timestamp,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remote position a vault message leaves changed, which is the one to request a sync of.
+fn synced_position(message: &YieldMessage) -> Option<([u8; 32], u64)> {
+    match message {
+        YieldMessage::DepositRequest { user, target_protocol_id, .. } => {
+            Some((*user, *target_protocol_id))
+        }
+        YieldMessage::WithdrawRequest { user, protocol_id, .. } => Some((*user, *protocol_id)),
+        YieldMessage::RebalanceRequest { user, to_protocol_id, .. } => {
+            Some((*user, *to_protocol_id))
+        }
+        _ => None,
+    }
+}
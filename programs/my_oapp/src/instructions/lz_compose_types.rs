@@ -0,0 +1,43 @@
+use crate::msg_codec::{decode_forwarded, CrossChainMessage};
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::ID as ENDPOINT_ID;
+use oapp::endpoint_cpi::{get_accounts_for_clear_compose, LzAccount};
+use oapp::LzComposeParams;
+
+#[derive(Accounts)]
+pub struct LzComposeTypes<'info> {
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+    /// CHECK: Protocol registry PDA, read only if it has been created
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub registry: UncheckedAccount<'info>,
+}
+
+impl<'info> LzComposeTypes<'info> {
+    /// Returns the accounts of `lz_compose` in order: the store, the endpoint clear_compose
+    /// accounts, then the accounts of the handler for the forwarded message.
+    pub fn apply(
+        ctx: &Context<LzComposeTypes>,
+        params: &LzComposeParams,
+    ) -> Result<Vec<LzAccount>> {
+        let (src_eid, message) = decode_forwarded(&params.message)?;
+        let message = CrossChainMessage::decode(message)?;
+
+        let store = ctx.accounts.store.key();
+        let mut accounts = vec![handlers::readonly(store)];
+        accounts.extend(get_accounts_for_clear_compose(
+            ENDPOINT_ID,
+            &params.from,
+            &store,
+            &params.guid,
+            params.index,
+            &params.message,
+        ));
+
+        let registry = ProtocolRegistry::load_unchecked(&ctx.accounts.registry)?;
+        accounts.extend(handlers::inbound_accounts(None));
+        accounts.extend(handlers::accounts(&registry, src_eid, &message.payload)?);
+        Ok(accounts)
+    }
+}
@@ -1,7 +1,12 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::{encode_forwarded, peek_message_type, YieldMessage};
 use crate::*;
 use anchor_lang::prelude::*;
 use oapp::endpoint::{
-    instructions::ClearParams, state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
+    cpi::accounts::{Clear, SendCompose},
+    instructions::{ClearParams, SendComposeParams},
+    state::EndpointSettings,
+    ConstructCPIContext, ENDPOINT_SEED, ID as ENDPOINT_ID,
 };
 use oapp::LzReceiveParams;
 
@@ -22,17 +27,41 @@ pub struct LzReceive<'info> {
             &store.key().to_bytes(),
             &params.src_eid.to_be_bytes()
         ],
-        bump = peer.bump
+        bump = peer.bump,
+        constraint = params.sender == peer.peer_address
     )]
-    pub peer: Account<'info, OAppPeerConfig>
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(
+        mut,
+        seeds = [NONCE_SEED, &store.key().to_bytes(), &params.src_eid.to_be_bytes(), &params.sender],
+        bump = nonce_account.bump
+    )]
+    pub nonce_account: Account<'info, Nonce>,
 }
 
 impl<'info> LzReceive<'info> {
-    pub fn apply(ctx: &mut Context<LzReceive>, params: &LzReceiveParams) -> Result<()> {
-        let message = msg_codec::decode(&params.message)?;
-        msg!("Received message: {}", message);
-        
-        // Clear the message from the endpoint
+    /// Remaining accounts are the endpoint clear accounts followed by the accounts of the
+    /// message handler, both in the order `lz_receive_types` returns them. A composed message
+    /// is recorded and queued for `lz_compose` instead of being handled here, with the endpoint
+    /// send_compose accounts in place of the handler accounts.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, LzReceive<'info>>,
+        params: &LzReceiveParams,
+    ) -> Result<()> {
+        handlers::check_message_type(peek_message_type(&params.message)?)?;
+
+        let message = YieldMessage::decode_with_header(&params.message)?;
+        message.validate_header()?;
+        message.payload.validate()?;
+
+        require!(
+            ctx.remaining_accounts.len() >= Clear::MIN_ACCOUNTS_LEN,
+            ErrorCode::AccountNotEnoughKeys
+        );
+        let (clear_accounts, handler_accounts) =
+            ctx.remaining_accounts.split_at(Clear::MIN_ACCOUNTS_LEN);
+
+        // Clear the message from the endpoint before acting on it
         let clear_params = ClearParams {
             receiver: ctx.accounts.store.key(),
             src_eid: params.src_eid,
@@ -41,18 +70,60 @@ impl<'info> LzReceive<'info> {
             guid: params.guid,
             message: params.message.clone(),
         };
-        
+
         // Prepare the seeds for the OApp Store PDA
         let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
-        
+
         oapp::endpoint_cpi::clear(
             ENDPOINT_ID,
             ctx.accounts.store.key(),
-            ctx.remaining_accounts,
+            clear_accounts,
             seeds,
             clear_params,
         )?;
-        
-        Ok(())
+
+        accept_nonce(&mut ctx.accounts.nonce_account, params.nonce)?;
+
+        if message.compose_msg.is_none() {
+            let mut inbound = handlers::Inbound::new(params.src_eid, handler_accounts)?;
+            handlers::record_delivery(&mut inbound, params.guid, &message)?;
+            handlers::guardian::authenticate(&mut inbound, &message)?;
+            return handlers::dispatch(&mut inbound, &message.payload);
+        }
+
+        require!(
+            handler_accounts.len() >= SendCompose::MIN_ACCOUNTS_LEN,
+            ErrorCode::AccountNotEnoughKeys
+        );
+        let (compose_accounts, inbound_accounts) =
+            handler_accounts.split_at(SendCompose::MIN_ACCOUNTS_LEN);
+        let mut inbound = handlers::Inbound::new(params.src_eid, inbound_accounts)?;
+        handlers::record_delivery(&mut inbound, params.guid, &message)?;
+
+        // The store composes to itself, so lz_compose runs the payload with its own accounts
+        oapp::endpoint_cpi::send_compose(
+            ENDPOINT_ID,
+            ctx.accounts.store.key(),
+            compose_accounts,
+            seeds,
+            SendComposeParams {
+                to: ctx.accounts.store.key(),
+                guid: params.guid,
+                index: 0,
+                message: encode_forwarded(params.src_eid, &params.message),
+            },
+        )
     }
-}
\ No newline at end of file
+}
+
+fn accept_nonce(nonce_account: &mut Account<Nonce>, nonce: u64) -> Result<()> {
+    let current_nonce = nonce_account.max_received_nonce;
+    if nonce_account.ordered_nonce {
+        require!(nonce == current_nonce + 1, MyOAppError::InvalidNonce);
+    }
+    // Track the max even when unordered, so turning ordering on rejects anything older
+    if nonce > current_nonce {
+        nonce_account.max_received_nonce = nonce;
+    }
+    Ok(())
+}
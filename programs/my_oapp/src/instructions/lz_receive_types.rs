@@ -1,8 +1,10 @@
+use crate::msg_codec::{encode_forwarded, peek_message_type, YieldMessage};
 use crate::*;
 use anchor_lang::prelude::*;
 use oapp::endpoint::{
     state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
 };
+use oapp::endpoint_cpi::{get_accounts_for_clear, get_accounts_for_send_compose, LzAccount};
 use oapp::LzReceiveParams;
 
 #[derive(Accounts)]
@@ -16,20 +18,70 @@ pub struct LzReceiveTypes<'info> {
         seeds::program = ENDPOINT_ID
     )]
     pub endpoint: Account<'info, EndpointSettings>,
+    /// CHECK: Protocol registry PDA, read only if it has been created
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub registry: UncheckedAccount<'info>,
 }
 
 impl<'info> LzReceiveTypes<'info> {
+    /// Returns the accounts of `lz_receive` in order: its named accounts, the endpoint clear
+    /// accounts, then the accounts of the handler for the message type. A composed message
+    /// only needs the endpoint send_compose accounts and the replay records, its handler
+    /// accounts being resolved by `lz_compose_types`.
     pub fn apply(
         ctx: &Context<LzReceiveTypes>,
         params: &LzReceiveParams,
-    ) -> Result<Vec<oapp::endpoint_cpi::LzAccount>> {
-        let accounts = vec![
-            oapp::endpoint_cpi::LzAccount {
-                pubkey: ctx.accounts.store.key(),
-                is_signer: false,
-                is_writable: false,
-            },
+    ) -> Result<Vec<LzAccount>> {
+        handlers::check_message_type(peek_message_type(&params.message)?)?;
+        let message = YieldMessage::decode_with_header(&params.message)?;
+
+        let store = ctx.accounts.store.key();
+        let (peer, _) = Pubkey::find_program_address(
+            &[PEER_SEED, &store.to_bytes(), &params.src_eid.to_be_bytes()],
+            ctx.program_id,
+        );
+        let (nonce_account, _) = Pubkey::find_program_address(
+            &[NONCE_SEED, &store.to_bytes(), &params.src_eid.to_be_bytes(), &params.sender],
+            ctx.program_id,
+        );
+        let mut accounts = vec![
+            handlers::readonly(store),
+            handlers::readonly(ctx.accounts.endpoint.key()),
+            handlers::readonly(peer),
+            handlers::writable(nonce_account),
         ];
+        accounts.extend(get_accounts_for_clear(
+            ENDPOINT_ID,
+            &store,
+            params.src_eid,
+            &params.sender,
+            params.nonce,
+        ));
+
+        if message.compose_msg.is_some() {
+            accounts.extend(get_accounts_for_send_compose(
+                ENDPOINT_ID,
+                &store,
+                &store,
+                &params.guid,
+                0,
+                &encode_forwarded(params.src_eid, &params.message),
+            ));
+            accounts.extend(handlers::inbound_accounts(Some((
+                params.src_eid,
+                &params.guid,
+                &message.header.message_id,
+            ))));
+            return Ok(accounts);
+        }
+
+        let registry = ProtocolRegistry::load_unchecked(&ctx.accounts.registry)?;
+        accounts.extend(handlers::inbound_accounts(Some((
+            params.src_eid,
+            &params.guid,
+            &message.header.message_id,
+        ))));
+        accounts.extend(handlers::accounts(&registry, params.src_eid, &message.payload)?);
         Ok(accounts)
     }
-}
\ No newline at end of file
+}
@@ -1,15 +1,61 @@
 pub mod send;
+pub mod send_yield_message;
+pub mod send_position_sync;
+pub mod send_withdrawal_release;
+pub mod send_deposit;
+pub mod release_deposit_escrow;
+pub mod refund_expired_deposit;
+pub mod quote_yield_message;
 pub mod init_store;
+pub mod lz_compose;
+pub mod lz_compose_types;
 pub mod lz_receive;
 pub mod lz_receive_types;
 pub mod quote_send;
-pub mod set_peer_config;
+pub mod init_peer;
+pub mod set_peer_address;
+pub mod set_enforced_options;
+pub mod set_message_type_options;
+pub mod close_peer;
+pub mod set_protocol_route;
+pub mod set_peer_credit;
+pub mod set_guardian;
+pub mod link_identity;
+pub mod unlink_identity;
+pub mod prune_received_messages;
+pub mod next_nonce;
+pub mod skip_inbound_nonce;
+pub mod set_ordered_nonce;
+pub mod get_optimal_strategy;
 // pub mod initialize_yield_aggregator; // Temporarily disabled
 
 pub use send::*;
+pub use send_yield_message::*;
+pub use send_position_sync::*;
+pub use send_withdrawal_release::*;
+pub use send_deposit::*;
+pub use release_deposit_escrow::*;
+pub use refund_expired_deposit::*;
+pub use quote_yield_message::*;
 pub use init_store::*;
+pub use lz_compose::*;
+pub use lz_compose_types::*;
 pub use lz_receive::*;
 pub use lz_receive_types::*;
 pub use quote_send::*;
-pub use set_peer_config::*;
+pub use init_peer::*;
+pub use set_peer_address::*;
+pub use set_enforced_options::*;
+pub use set_message_type_options::*;
+pub use close_peer::*;
+pub use set_protocol_route::*;
+pub use set_peer_credit::*;
+pub use set_guardian::*;
+pub use link_identity::*;
+pub use unlink_identity::*;
+pub use prune_received_messages::*;
+pub use next_nonce::*;
+pub use skip_inbound_nonce::*;
+pub use set_ordered_nonce::*;
+pub use get_optimal_strategy::*;
 // pub use initialize_yield_aggregator::*; // Temporarily disabled
\ No newline at end of file
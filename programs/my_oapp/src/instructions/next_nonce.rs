@@ -0,0 +1,37 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+/// Called by the executor before delivery. The accounts and params layout is fixed by the
+/// executor and must not change.
+#[derive(Accounts)]
+#[instruction(params: NextNonceParams)]
+pub struct NextNonce<'info> {
+    #[account(
+        seeds = [STORE_SEED],
+        bump = store.bump,
+        constraint = params.receiver == store.key()
+    )]
+    pub store: Account<'info, OAppStore>,
+    #[account(
+        seeds = [NONCE_SEED, &params.receiver.as_ref(), &params.src_eid.to_be_bytes(), &params.sender],
+        bump = nonce_account.bump
+    )]
+    pub nonce_account: Account<'info, Nonce>,
+}
+
+impl NextNonce<'_> {
+    pub fn apply(ctx: &Context<NextNonce>, _params: &NextNonceParams) -> Result<u64> {
+        let nonce_account = &ctx.accounts.nonce_account;
+        if nonce_account.ordered_nonce {
+            return Ok(nonce_account.max_received_nonce + 1);
+        }
+        Ok(0) // Path nonces start from 1, so 0 means no ordering is enforced
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct NextNonceParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub receiver: Pubkey,
+}
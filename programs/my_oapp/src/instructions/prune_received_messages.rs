@@ -0,0 +1,37 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::MAX_MESSAGE_AGE;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PruneReceivedMessages<'info> {
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    /// Receives the rent of the closed records
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> PruneReceivedMessages<'info> {
+    /// Closes the `ReceivedMessage` records passed as remaining accounts. Only records whose
+    /// header timestamp is past the replay window can go, since a replay of those messages
+    /// already fails `validate_header`.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, PruneReceivedMessages<'info>>,
+        params: &PruneReceivedMessagesParams,
+    ) -> Result<()> {
+        let cutoff = Clock::get()?.unix_timestamp - MAX_MESSAGE_AGE;
+        for info in ctx.remaining_accounts {
+            let record: Account<ReceivedMessage> = Account::try_from(info)?;
+            require!(record.src_eid == params.src_eid, MyOAppError::InvalidAccount);
+            require!(record.timestamp < cutoff, MyOAppError::MessageNotExpired);
+            record.close(ctx.accounts.admin.to_account_info())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct PruneReceivedMessagesParams {
+    pub src_eid: u32,
+}
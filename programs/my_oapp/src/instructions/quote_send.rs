@@ -28,7 +28,7 @@ pub struct QuoteSend<'info> {
 
 impl<'info> QuoteSend<'info> {
     pub fn apply(ctx: &Context<QuoteSend>, params: &QuoteSendParams) -> Result<MessagingFee> {
-        let message = msg_codec::encode(&params.message);
+        let message = msg_codec::encode(&params.message, params.compose_msg.as_deref());
         let quote_params = QuoteParams {
             dst_eid: params.dst_eid,
             sender: ctx.accounts.store.key(),
@@ -38,7 +38,7 @@ impl<'info> QuoteSend<'info> {
                 .accounts
                 .peer
                 .enforced_options
-                .combine_options(&None::<Vec<u8>>, &params.options)?,
+                .combine_options(&params.compose_msg, &params.options)?,
             pay_in_lz_token: params.pay_in_lz_token,
         };
         oapp::endpoint_cpi::quote(
@@ -54,5 +54,6 @@ pub struct QuoteSendParams {
     pub dst_eid: u32,
     pub message: String,
     pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>,
     pub pay_in_lz_token: bool,
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use crate::msg_codec::{GuardianSignature, YieldMessage};
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    instructions::QuoteParams, state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
+    MessagingFee,
+};
+
+#[derive(Accounts)]
+#[instruction(params: QuoteYieldMessageParams)]
+pub struct QuoteYieldMessage<'info> {
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Account<'info, EndpointSettings>,
+    #[account(
+        seeds = [
+            PEER_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes()
+        ],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(
+        seeds = [
+            MESSAGE_TYPE_OPTIONS_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes(),
+            &[params.message.get_message_type()]
+        ],
+        bump = type_options.bump
+    )]
+    /// Enforced options for the message type, if any are set
+    pub type_options: Option<Account<'info, MessageTypeOptions>>,
+}
+
+impl<'info> QuoteYieldMessage<'info> {
+    /// Quotes `send_yield_message` for the same params. The header is stamped the same way,
+    /// so the quoted message has the size of the one that will be sent.
+    pub fn apply(
+        ctx: &Context<QuoteYieldMessage>,
+        params: &QuoteYieldMessageParams,
+    ) -> Result<MessagingFee> {
+        let nonce = ctx.accounts.peer.outbound_nonce + 1;
+        let message = super::send_yield_message::stamp(
+            &params.message,
+            nonce,
+            &params.compose_msg,
+            &params.guardian_signature,
+        )?
+        .encode()?;
+
+        let quote_params = QuoteParams {
+            dst_eid: params.dst_eid,
+            sender: ctx.accounts.store.key(),
+            receiver: ctx.accounts.peer.peer_address,
+            message,
+            options: MessageTypeOptions::select(&ctx.accounts.type_options, &ctx.accounts.peer)
+                .combine_options(&params.compose_msg, &params.options)?,
+            pay_in_lz_token: params.pay_in_lz_token,
+        };
+        oapp::endpoint_cpi::quote(ENDPOINT_ID, ctx.remaining_accounts, quote_params)
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct QuoteYieldMessageParams {
+    pub dst_eid: u32,
+    pub message: YieldMessage,
+    pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>,
+    pub guardian_signature: Option<GuardianSignature>,
+    pub pay_in_lz_token: bool,
+}
@@ -0,0 +1,51 @@
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RefundExpiredDeposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [DEPOSIT_ESCROW_SEED, &escrow.dst_eid.to_be_bytes(), &escrow.nonce.to_be_bytes()],
+        bump = escrow.bump,
+        has_one = user @ MyOAppError::Unauthorized,
+        has_one = mint @ MyOAppError::InvalidAccount,
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+    #[account(
+        mut,
+        seeds = [OUTBOUND_MESSAGE_SEED, &escrow.dst_eid.to_be_bytes(), &escrow.nonce.to_be_bytes()],
+        bump = outbound_message.bump
+    )]
+    pub outbound_message: Account<'info, OutboundMessage>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = escrow)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::authority = user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> RefundExpiredDeposit<'info> {
+    /// Returns escrowed deposit tokens to the user once the deposit has failed on the
+    /// destination, or `DEPOSIT_REFUND_GRACE_PERIOD` has passed after its deadline without an
+    /// ack. The destination rejects a DepositRequest delivered after its deadline, and the
+    /// grace period outlasts any ack of one executed in time.
+    pub fn apply(ctx: &mut Context<RefundExpiredDeposit>) -> Result<()> {
+        ctx.accounts
+            .outbound_message
+            .expire(ctx.accounts.escrow.deadline, Clock::get()?.unix_timestamp)?;
+
+        super::release_deposit_escrow::empty_escrow(
+            &ctx.accounts.escrow,
+            &ctx.accounts.escrow_token_account,
+            ctx.accounts.user_token_account.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            &ctx.accounts.token_program,
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct ReleaseDepositEscrow<'info> {
+    /// Pays for the custody account if it doesn't exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [DEPOSIT_ESCROW_SEED, &escrow.dst_eid.to_be_bytes(), &escrow.nonce.to_be_bytes()],
+        bump = escrow.bump,
+        has_one = user @ MyOAppError::InvalidAccount,
+        has_one = mint @ MyOAppError::InvalidAccount,
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+    #[account(
+        seeds = [OUTBOUND_MESSAGE_SEED, &escrow.dst_eid.to_be_bytes(), &escrow.nonce.to_be_bytes()],
+        bump = outbound_message.bump
+    )]
+    pub outbound_message: Account<'info, OutboundMessage>,
+    /// CHECK: Gets the rent of the escrow accounts back
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = escrow)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA holding the liquidity that backs remote users
+    #[account(seeds = [REMOTE_AUTHORITY_SEED], bump)]
+    pub remote_authority: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = remote_authority,
+    )]
+    pub custody_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReleaseDepositEscrow<'info> {
+    /// Moves the tokens of an acknowledged deposit into the remote authority's custody, since
+    /// the destination has credited the user from its own liquidity. Anyone can call it.
+    pub fn apply(ctx: &mut Context<ReleaseDepositEscrow>) -> Result<()> {
+        require!(
+            ctx.accounts.outbound_message.status == OutboundStatus::Acknowledged,
+            MyOAppError::DepositNotAcknowledged
+        );
+        empty_escrow(
+            &ctx.accounts.escrow,
+            &ctx.accounts.escrow_token_account,
+            ctx.accounts.custody_token_account.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            &ctx.accounts.token_program,
+        )
+    }
+}
+
+/// Transfers everything in the escrow token account to `to` and closes it, its rent going
+/// to `rent_to`. The escrow account itself is closed by the caller's constraints.
+pub(crate) fn empty_escrow<'info>(
+    escrow: &Account<'info, DepositEscrow>,
+    escrow_token_account: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    rent_to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let dst_eid = escrow.dst_eid.to_be_bytes();
+    let nonce = escrow.nonce.to_be_bytes();
+    let seeds: &[&[u8]] = &[DEPOSIT_ESCROW_SEED, &dst_eid, &nonce, &[escrow.bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: escrow_token_account.to_account_info(),
+                to,
+                authority: escrow.to_account_info(),
+            },
+            &[seeds],
+        ),
+        escrow_token_account.amount,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow_token_account.to_account_info(),
+            destination: rent_to,
+            authority: escrow.to_account_info(),
+        },
+        &[seeds],
+    ))
+}
@@ -27,7 +27,7 @@ pub struct Send<'info> {
 impl<'info> Send<'info> {
     pub fn apply(ctx: &mut Context<Send>, params: &SendMessageParams) -> Result<()> {
         // Serialize the message according to our codec
-        let message = msg_codec::encode(&params.message);
+        let message = msg_codec::encode(&params.message, params.compose_msg.as_deref());
         // Prepare the seeds for the OApp Store PDA, which is used to sign the CPI call to the Endpoint program.
         let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
 
@@ -40,7 +40,7 @@ impl<'info> Send<'info> {
                 .accounts
                 .peer
                 .enforced_options
-                .combine_options(&None::<Vec<u8>>, &params.options)?,
+                .combine_options(&params.compose_msg, &params.options)?,
             native_fee: params.native_fee,
             lz_token_fee: params.lz_token_fee,
         };
@@ -61,6 +61,7 @@ pub struct SendMessageParams {
     pub dst_eid: u32,
     pub message: String,
     pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>,
     pub native_fee: u64,
     pub lz_token_fee: u64,
 }
\ No newline at end of file
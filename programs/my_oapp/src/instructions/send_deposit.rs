@@ -0,0 +1,158 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::{MsgCodecError, YieldMessage, DEPOSIT_REQUEST_TYPE};
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use oapp::endpoint::{
+    instructions::SendParams, state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+#[instruction(params: SendDepositParams)]
+pub struct SendDeposit<'info> {
+    /// User the deposit is for. Pays for the ledger entry and the escrow.
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PEER_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes()
+        ],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Account<'info, EndpointSettings>,
+    #[account(
+        seeds = [
+            MESSAGE_TYPE_OPTIONS_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes(),
+            &[DEPOSIT_REQUEST_TYPE]
+        ],
+        bump = type_options.bump
+    )]
+    /// Enforced options for DepositRequest messages, if any are set
+    pub type_options: Option<Account<'info, MessageTypeOptions>>,
+    #[account(
+        init,
+        payer = user,
+        seeds = [
+            OUTBOUND_MESSAGE_SEED,
+            &params.dst_eid.to_be_bytes(),
+            &(peer.outbound_nonce + 1).to_be_bytes()
+        ],
+        bump,
+        space = 8 + OutboundMessage::INIT_SPACE,
+    )]
+    pub outbound_message: Account<'info, OutboundMessage>,
+    #[account(
+        init,
+        payer = user,
+        seeds = [
+            DEPOSIT_ESCROW_SEED,
+            &params.dst_eid.to_be_bytes(),
+            &(peer.outbound_nonce + 1).to_be_bytes()
+        ],
+        bump,
+        space = 8 + DepositEscrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SendDeposit<'info> {
+    /// Sends a `DepositRequest` to a peer and locks its tokens in escrow until the destination
+    /// acks it. See `release_deposit_escrow` and `refund_expired_deposit` for the two ways out.
+    /// Remaining accounts are the endpoint send accounts.
+    pub fn apply(ctx: &mut Context<SendDeposit>, params: &SendDepositParams) -> Result<()> {
+        let YieldMessage::DepositRequest { user, amount, token_mint, deadline, .. } =
+            &params.message
+        else {
+            return err!(MsgCodecError::UnsupportedMessageType);
+        };
+        require!(ctx.accounts.user.key().to_bytes() == *user, MyOAppError::Unauthorized);
+        require_keys_eq!(ctx.accounts.mint.key(), *token_mint, MyOAppError::InvalidAccount);
+        params.message.validate()?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.escrow_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            *amount,
+        )?;
+
+        let nonce = ctx.accounts.peer.outbound_nonce + 1;
+        let stamped =
+            super::send_yield_message::stamp(&params.message, nonce, &params.compose_msg, &None)?;
+        ctx.accounts.peer.outbound_nonce = nonce;
+
+        let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
+        let send_params = SendParams {
+            dst_eid: params.dst_eid,
+            receiver: ctx.accounts.peer.peer_address,
+            message: stamped.encode()?,
+            options: MessageTypeOptions::select(&ctx.accounts.type_options, &ctx.accounts.peer)
+                .combine_options(&params.compose_msg, &params.options)?,
+            native_fee: params.native_fee,
+            lz_token_fee: params.lz_token_fee,
+        };
+        let receipt = oapp::endpoint_cpi::send(
+            ENDPOINT_ID,
+            ctx.accounts.store.key(),
+            ctx.remaining_accounts,
+            seeds,
+            send_params,
+        )?;
+
+        ctx.accounts.outbound_message.set_inner(OutboundMessage::sent(
+            params.dst_eid,
+            &stamped,
+            &receipt,
+            stamped.timestamp,
+            ctx.bumps.outbound_message,
+        ));
+        ctx.accounts.escrow.set_inner(DepositEscrow {
+            user: ctx.accounts.user.key(),
+            mint: *token_mint,
+            amount: *amount,
+            dst_eid: params.dst_eid,
+            nonce,
+            deadline: *deadline,
+            bump: ctx.bumps.escrow,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SendDepositParams {
+    pub dst_eid: u32,
+    pub message: YieldMessage, // Must be a DepositRequest
+    pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
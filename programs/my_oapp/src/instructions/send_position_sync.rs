@@ -0,0 +1,138 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::{YieldMessage, POSITION_SYNC_TYPE};
+use crate::*;
+use ::yield_aggregator::state::YieldVault;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    instructions::SendParams, state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+#[instruction(params: SendPositionSyncParams)]
+pub struct SendPositionSync<'info> {
+    /// Pays for the outbound ledger entry
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PEER_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes()
+        ],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Account<'info, EndpointSettings>,
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump = registry.bump)]
+    pub registry: Account<'info, ProtocolRegistry>,
+    #[account(
+        seeds = [
+            REMOTE_POSITION_SEED,
+            &params.dst_eid.to_be_bytes(),
+            &params.user,
+            &params.protocol_id.to_be_bytes()
+        ],
+        bump = position.bump
+    )]
+    /// Position of `user` on the destination, which is the chain it is reported to
+    pub position: Account<'info, RemotePosition>,
+    /// Vault the position's shares are in, used to value them
+    pub yield_vault: Account<'info, YieldVault>,
+    #[account(
+        seeds = [
+            MESSAGE_TYPE_OPTIONS_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes(),
+            &[POSITION_SYNC_TYPE]
+        ],
+        bump = type_options.bump
+    )]
+    /// Enforced options for PositionSync messages, if any are set
+    pub type_options: Option<Account<'info, MessageTypeOptions>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            OUTBOUND_MESSAGE_SEED,
+            &params.dst_eid.to_be_bytes(),
+            &(peer.outbound_nonce + 1).to_be_bytes()
+        ],
+        bump,
+        space = 8 + OutboundMessage::INIT_SPACE,
+    )]
+    pub outbound_message: Account<'info, OutboundMessage>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SendPositionSync<'info> {
+    /// Reports a remote position back to a peer as a `PositionSync`. It only reads state, so
+    /// anyone can send it, typically in answer to `PositionSyncRequested`. Remaining accounts
+    /// are the endpoint send accounts, whose payer covers the fee.
+    pub fn apply(ctx: &mut Context<SendPositionSync>, params: &SendPositionSyncParams) -> Result<()> {
+        let route = handlers::route(&ctx.accounts.registry, params.protocol_id)?;
+        require_keys_eq!(
+            ctx.accounts.yield_vault.key(),
+            handlers::ProtocolKeys::new(&route).yield_vault,
+            MyOAppError::InvalidAccount
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let position = &ctx.accounts.position;
+        let value = ctx.accounts.yield_vault.preview_redeem(position.shares)?;
+        let message = YieldMessage::PositionSync {
+            user: position.owner.address,
+            protocol_id: position.protocol_id,
+            principal_amount: position.principal,
+            yield_earned: value.saturating_sub(position.principal),
+            total_shares: position.shares,
+            last_compound_time: position.last_activity,
+            position_health: 100,
+            sync_timestamp: now,
+        };
+
+        let nonce = ctx.accounts.peer.outbound_nonce + 1;
+        let stamped = message.to_cross_chain(nonce, now, None);
+        ctx.accounts.peer.outbound_nonce = nonce;
+
+        let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
+        let send_params = SendParams {
+            dst_eid: params.dst_eid,
+            receiver: ctx.accounts.peer.peer_address,
+            message: stamped.encode()?,
+            options: MessageTypeOptions::select(&ctx.accounts.type_options, &ctx.accounts.peer)
+                .combine_options(&None::<Vec<u8>>, &params.options)?,
+            native_fee: params.native_fee,
+            lz_token_fee: params.lz_token_fee,
+        };
+        let receipt = oapp::endpoint_cpi::send(
+            ENDPOINT_ID,
+            ctx.accounts.store.key(),
+            ctx.remaining_accounts,
+            seeds,
+            send_params,
+        )?;
+
+        ctx.accounts.outbound_message.set_inner(OutboundMessage::sent(
+            params.dst_eid,
+            &stamped,
+            &receipt,
+            now,
+            ctx.bumps.outbound_message,
+        ));
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SendPositionSyncParams {
+    pub dst_eid: u32,
+    pub user: [u8; 32], // Address of the position owner on dst_eid, see UniversalAddress
+    pub protocol_id: u64,
+    pub options: Vec<u8>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
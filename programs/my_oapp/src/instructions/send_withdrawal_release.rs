@@ -0,0 +1,127 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::WITHDRAWAL_RELEASE_TYPE;
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    instructions::SendParams, state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+pub struct SendWithdrawalRelease<'info> {
+    /// Pays for the outbound ledger entry and gets the rent of the pending withdrawal
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PEER_SEED,
+            &store.key().to_bytes(),
+            &pending.dst_eid.to_be_bytes()
+        ],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Account<'info, EndpointSettings>,
+    /// Position the assets were redeemed from
+    #[account(mut)]
+    pub position: Account<'info, RemotePosition>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [
+            PENDING_WITHDRAWAL_SEED,
+            position.key().as_ref(),
+            &pending.dst_eid.to_be_bytes(),
+            &pending.destination_address
+        ],
+        bump = pending.bump
+    )]
+    pub pending: Account<'info, PendingWithdrawal>,
+    #[account(
+        seeds = [
+            MESSAGE_TYPE_OPTIONS_SEED,
+            &store.key().to_bytes(),
+            &pending.dst_eid.to_be_bytes(),
+            &[WITHDRAWAL_RELEASE_TYPE]
+        ],
+        bump = type_options.bump
+    )]
+    /// Enforced options for WithdrawalRelease messages, if any are set
+    pub type_options: Option<Account<'info, MessageTypeOptions>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            OUTBOUND_MESSAGE_SEED,
+            &pending.dst_eid.to_be_bytes(),
+            &(peer.outbound_nonce + 1).to_be_bytes()
+        ],
+        bump,
+        space = 8 + OutboundMessage::INIT_SPACE,
+    )]
+    pub outbound_message: Account<'info, OutboundMessage>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SendWithdrawalRelease<'info> {
+    /// Sends the return leg of withdrawals waiting in custody as a `WithdrawalRelease`, which
+    /// the destination pays out from its own custody. The destination was fixed by the
+    /// withdrawal request, so anyone can send it, typically in answer to
+    /// `WithdrawalReturnRequested`. Remaining accounts are the endpoint send accounts, whose
+    /// payer covers the fee.
+    pub fn apply(
+        ctx: &mut Context<SendWithdrawalRelease>,
+        params: &SendWithdrawalReleaseParams,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pending = &ctx.accounts.pending;
+        let message = pending.release_message(&ctx.accounts.position, now);
+        let position = &mut ctx.accounts.position;
+        position.pending_withdrawal = position
+            .pending_withdrawal
+            .checked_sub(pending.amount)
+            .ok_or(MyOAppError::MathOverflow)?;
+        position.last_activity = now;
+
+        let nonce = ctx.accounts.peer.outbound_nonce + 1;
+        let stamped = message.to_cross_chain(nonce, now, None);
+        ctx.accounts.peer.outbound_nonce = nonce;
+
+        let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
+        let send_params = SendParams {
+            dst_eid: pending.dst_eid,
+            receiver: ctx.accounts.peer.peer_address,
+            message: stamped.encode()?,
+            options: MessageTypeOptions::select(&ctx.accounts.type_options, &ctx.accounts.peer)
+                .combine_options(&None::<Vec<u8>>, &params.options)?,
+            native_fee: params.native_fee,
+            lz_token_fee: params.lz_token_fee,
+        };
+        let receipt = oapp::endpoint_cpi::send(
+            ENDPOINT_ID,
+            ctx.accounts.store.key(),
+            ctx.remaining_accounts,
+            seeds,
+            send_params,
+        )?;
+
+        ctx.accounts.outbound_message.set_inner(OutboundMessage::sent(
+            pending.dst_eid,
+            &stamped,
+            &receipt,
+            now,
+            ctx.bumps.outbound_message,
+        ));
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SendWithdrawalReleaseParams {
+    pub options: Vec<u8>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
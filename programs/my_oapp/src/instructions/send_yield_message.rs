@@ -0,0 +1,146 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::{is_user_message, CrossChainMessage, GuardianSignature, YieldMessage};
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    instructions::SendParams, state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+#[instruction(params: SendYieldMessageParams)]
+pub struct SendYieldMessage<'info> {
+    /// User messages are signed by the user they act for or the account linked to it, everything
+    /// else by the store admin. Pays for the outbound ledger entry.
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PEER_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes()
+        ],
+        bump = peer.bump
+    )]
+    /// Destination peer. Also tracks the outbound nonce for this pathway.
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    /// OApp Store PDA that signs the send instruction
+    pub store: Account<'info, OAppStore>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Account<'info, EndpointSettings>,
+    #[account(
+        seeds = [
+            MESSAGE_TYPE_OPTIONS_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes(),
+            &[params.message.get_message_type()]
+        ],
+        bump = type_options.bump
+    )]
+    /// Enforced options for the message type, if any are set
+    pub type_options: Option<Account<'info, MessageTypeOptions>>,
+    #[account(
+        init,
+        payer = sender,
+        seeds = [
+            OUTBOUND_MESSAGE_SEED,
+            &params.dst_eid.to_be_bytes(),
+            &(peer.outbound_nonce + 1).to_be_bytes()
+        ],
+        bump,
+        space = 8 + OutboundMessage::INIT_SPACE,
+    )]
+    pub outbound_message: Account<'info, OutboundMessage>,
+    /// Lets the sender act for the user of a message when it is linked to that user's address
+    /// on the destination
+    pub identity_link: Option<Account<'info, IdentityLink>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SendYieldMessage<'info> {
+    pub fn apply(ctx: &mut Context<SendYieldMessage>, params: &SendYieldMessageParams) -> Result<()> {
+        let message = &params.message;
+        // Deposits go through send_deposit, which escrows their tokens
+        require!(
+            !matches!(message, YieldMessage::DepositRequest { .. }),
+            MyOAppError::UnsupportedAction
+        );
+        match message.initiator() {
+            Some(initiator) if is_user_message(message.get_message_type()) => {
+                let sender = ctx.accounts.sender.key();
+                let linked = ctx.accounts.identity_link.as_ref().is_some_and(|link| {
+                    link.solana == sender
+                        && link.universal == UniversalAddress::new(params.dst_eid, initiator)
+                });
+                require!(sender.to_bytes() == initiator || linked, MyOAppError::Unauthorized)
+            }
+            _ => require_keys_eq!(
+                ctx.accounts.sender.key(),
+                ctx.accounts.store.admin,
+                MyOAppError::Unauthorized
+            ),
+        }
+        message.validate()?;
+
+        // Stamp the header with the next nonce for this peer
+        let nonce = ctx.accounts.peer.outbound_nonce + 1;
+        let stamped = stamp(message, nonce, &params.compose_msg, &params.guardian_signature)?;
+        ctx.accounts.peer.outbound_nonce = nonce;
+
+        let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
+        let send_params = SendParams {
+            dst_eid: params.dst_eid,
+            receiver: ctx.accounts.peer.peer_address,
+            message: stamped.encode()?,
+            options: MessageTypeOptions::select(&ctx.accounts.type_options, &ctx.accounts.peer)
+                .combine_options(&params.compose_msg, &params.options)?,
+            native_fee: params.native_fee,
+            lz_token_fee: params.lz_token_fee,
+        };
+        let receipt = oapp::endpoint_cpi::send(
+            ENDPOINT_ID,
+            ctx.accounts.store.key(),
+            ctx.remaining_accounts,
+            seeds,
+            send_params,
+        )?;
+
+        ctx.accounts.outbound_message.set_inner(OutboundMessage::sent(
+            params.dst_eid,
+            &stamped,
+            &receipt,
+            Clock::get()?.unix_timestamp,
+            ctx.bumps.outbound_message,
+        ));
+        Ok(())
+    }
+}
+
+/// Stamps `message` with its header. A guardian signature fixes the timestamp, so it only
+/// matches the message id if `nonce` is the one the guardian signed for.
+pub(crate) fn stamp(
+    message: &YieldMessage,
+    nonce: u64,
+    compose_msg: &Option<Vec<u8>>,
+    guardian_signature: &Option<GuardianSignature>,
+) -> Result<CrossChainMessage> {
+    let timestamp = match guardian_signature {
+        Some(signed) => signed.timestamp,
+        None => Clock::get()?.unix_timestamp,
+    };
+    let mut cross_chain = message.to_cross_chain(nonce, timestamp, compose_msg.clone());
+    cross_chain.signature = guardian_signature.as_ref().map(|signed| signed.signature.to_vec());
+    Ok(cross_chain)
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SendYieldMessageParams {
+    pub dst_eid: u32,
+    pub message: YieldMessage,
+    pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>, // See COMPOSE_FLAGS_OFFSET in msg_codec
+    pub guardian_signature: Option<GuardianSignature>, // Privileged messages only
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
@@ -0,0 +1,38 @@
+use crate::errors::MyOAppError;
+use crate::events::EnforcedOptionsUpdated;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetEnforcedOptionsParams)]
+pub struct SetEnforcedOptions<'info> {
+    #[account(
+        mut,
+        seeds = [PEER_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    pub admin: Signer<'info>,
+}
+
+impl SetEnforcedOptions<'_> {
+    pub fn apply(ctx: &mut Context<SetEnforcedOptions>, params: &SetEnforcedOptionsParams) -> Result<()> {
+        params.enforced_options.validate()?;
+        ctx.accounts.peer.enforced_options = params.enforced_options.clone();
+
+        emit!(EnforcedOptionsUpdated {
+            eid: params.dst_eid,
+            send: params.enforced_options.send.clone(),
+            send_and_call: params.enforced_options.send_and_call.clone(),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetEnforcedOptionsParams {
+    pub dst_eid: u32,
+    pub enforced_options: EnforcedOptions,
+}
@@ -0,0 +1,38 @@
+use crate::errors::MyOAppError;
+use crate::events::GuardianUpdated;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [GUARDIAN_SEED],
+        bump,
+        space = 8 + GuardianConfig::INIT_SPACE,
+    )]
+    pub guardian_config: Account<'info, GuardianConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetGuardian<'info> {
+    /// `None` turns guardian signatures off again.
+    pub fn apply(ctx: &mut Context<SetGuardian>, params: &SetGuardianParams) -> Result<()> {
+        let guardian_config = &mut ctx.accounts.guardian_config;
+        guardian_config.guardian = params.guardian;
+        guardian_config.bump = ctx.bumps.guardian_config;
+
+        emit!(GuardianUpdated { guardian: params.guardian });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetGuardianParams {
+    pub guardian: Option<Pubkey>,
+}
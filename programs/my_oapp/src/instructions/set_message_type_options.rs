@@ -0,0 +1,63 @@
+use crate::errors::MyOAppError;
+use crate::events::MessageTypeOptionsUpdated;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetMessageTypeOptionsParams)]
+pub struct SetMessageTypeOptions<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [
+            MESSAGE_TYPE_OPTIONS_SEED,
+            &store.key().to_bytes(),
+            &params.dst_eid.to_be_bytes(),
+            &[params.msg_type]
+        ],
+        bump,
+        space = MessageTypeOptions::SIZE,
+    )]
+    pub type_options: Account<'info, MessageTypeOptions>,
+    #[account(
+        seeds = [PEER_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetMessageTypeOptions<'info> {
+    pub fn apply(
+        ctx: &mut Context<SetMessageTypeOptions>,
+        params: &SetMessageTypeOptionsParams,
+    ) -> Result<()> {
+        handlers::check_message_type(params.msg_type)?;
+        params.enforced_options.validate()?;
+
+        let type_options = &mut ctx.accounts.type_options;
+        type_options.eid = params.dst_eid;
+        type_options.msg_type = params.msg_type;
+        type_options.enforced_options = params.enforced_options.clone();
+        type_options.bump = ctx.bumps.type_options;
+
+        emit!(MessageTypeOptionsUpdated {
+            eid: params.dst_eid,
+            msg_type: params.msg_type,
+            send: params.enforced_options.send.clone(),
+            send_and_call: params.enforced_options.send_and_call.clone(),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetMessageTypeOptionsParams {
+    pub dst_eid: u32,
+    pub msg_type: u8,
+    pub enforced_options: EnforcedOptions,
+}
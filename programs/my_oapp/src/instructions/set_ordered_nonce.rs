@@ -0,0 +1,37 @@
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetOrderedNonceParams)]
+pub struct SetOrderedNonce<'info> {
+    #[account(address = store.admin @ MyOAppError::Unauthorized)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [STORE_SEED], bump = store.bump)]
+    pub store: Account<'info, OAppStore>,
+    #[account(
+        seeds = [PEER_SEED, &store.key().to_bytes(), &params.src_eid.to_be_bytes()],
+        bump = peer.bump
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(
+        mut,
+        seeds = [NONCE_SEED, &store.key().to_bytes(), &params.src_eid.to_be_bytes(), &peer.peer_address],
+        bump = nonce_account.bump
+    )]
+    pub nonce_account: Account<'info, Nonce>,
+}
+
+impl SetOrderedNonce<'_> {
+    /// Once ordering is on, nonces at or below the highest one already received are rejected.
+    pub fn apply(ctx: &mut Context<SetOrderedNonce>, params: &SetOrderedNonceParams) -> Result<()> {
+        ctx.accounts.nonce_account.ordered_nonce = params.ordered_nonce;
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetOrderedNonceParams {
+    pub src_eid: u32,
+    pub ordered_nonce: bool,
+}
@@ -0,0 +1,61 @@
+use crate::errors::MyOAppError;
+use crate::events::PeerAddressUpdated;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetPeerAddressParams)]
+pub struct SetPeerAddress<'info> {
+    #[account(
+        mut,
+        seeds = [PEER_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = peer.bump,
+        constraint = peer.peer_address != params.peer_address @ MyOAppError::PeerAddressUnchanged
+    )]
+    pub peer: Account<'info, OAppPeerConfig>,
+    #[account(
+        seeds = [NONCE_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes(), &peer.peer_address],
+        bump = old_nonce_account.bump
+    )]
+    pub old_nonce_account: Account<'info, Nonce>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [NONCE_SEED, &store.key().to_bytes(), &params.dst_eid.to_be_bytes(), &params.peer_address],
+        bump,
+        space = 8 + Nonce::INIT_SPACE,
+    )]
+    pub nonce_account: Account<'info, Nonce>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetPeerAddress<'info> {
+    /// Inbound nonces are tracked per sender, so the new address gets its own nonce account
+    /// with the ordering setting of the old one. Messages still in flight from the old address
+    /// are rejected by `lz_receive` from now on.
+    pub fn apply(ctx: &mut Context<SetPeerAddress>, params: &SetPeerAddressParams) -> Result<()> {
+        let old_peer_address = ctx.accounts.peer.peer_address;
+        ctx.accounts.peer.peer_address = params.peer_address;
+
+        let nonce_account = &mut ctx.accounts.nonce_account;
+        nonce_account.ordered_nonce = ctx.accounts.old_nonce_account.ordered_nonce;
+        nonce_account.bump = ctx.bumps.nonce_account;
+
+        emit!(PeerAddressUpdated {
+            eid: params.dst_eid,
+            old_peer_address,
+            peer_address: params.peer_address,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetPeerAddressParams {
+    pub dst_eid: u32,
+    pub peer_address: [u8; 32],
+}
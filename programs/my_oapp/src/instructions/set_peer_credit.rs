@@ -0,0 +1,39 @@
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetPeerCreditParams)]
+pub struct SetPeerCredit<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [PEER_CREDIT_SEED, &params.eid.to_be_bytes()],
+        bump,
+        space = 8 + PeerCredit::INIT_SPACE,
+    )]
+    pub peer_credit: Account<'info, PeerCredit>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetPeerCredit<'info> {
+    /// Sets how much custody liquidity deposits from `eid` may have outstanding. Lowering it
+    /// below what is in use only blocks new deposits.
+    pub fn apply(ctx: &mut Context<SetPeerCredit>, params: &SetPeerCreditParams) -> Result<()> {
+        let peer_credit = &mut ctx.accounts.peer_credit;
+        peer_credit.eid = params.eid;
+        peer_credit.limit = params.limit;
+        peer_credit.bump = ctx.bumps.peer_credit;
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetPeerCreditParams {
+    pub eid: u32,
+    pub limit: u64,
+}
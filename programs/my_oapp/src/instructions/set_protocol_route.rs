@@ -0,0 +1,51 @@
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetProtocolRoute<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [PROTOCOL_REGISTRY_SEED],
+        bump,
+        space = 8 + ProtocolRegistry::INIT_SPACE,
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    #[account(seeds = [STORE_SEED], bump = store.bump, has_one = admin @ MyOAppError::Unauthorized)]
+    pub store: Account<'info, OAppStore>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetProtocolRoute<'info> {
+    pub fn apply(ctx: &mut Context<SetProtocolRoute>, params: &SetProtocolRouteParams) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.bump = ctx.bumps.registry;
+
+        let route = ProtocolRoute {
+            protocol_id: params.protocol_id,
+            name: params.protocol_name.clone(),
+            mint: params.mint,
+        };
+        match registry.routes.iter_mut().find(|r| r.protocol_id == params.protocol_id) {
+            Some(existing) => *existing = route,
+            None => {
+                require!(
+                    registry.routes.len() < MAX_PROTOCOL_ROUTES,
+                    MyOAppError::TooManyProtocols
+                );
+                registry.routes.push(route);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetProtocolRouteParams {
+    pub protocol_id: u64,
+    pub protocol_name: String,
+    pub mint: Pubkey,
+}
@@ -0,0 +1,61 @@
+use crate::errors::MyOAppError;
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{instructions::SkipParams, ID as ENDPOINT_ID};
+
+#[derive(Accounts)]
+#[instruction(params: SkipInboundNonceParams)]
+pub struct SkipInboundNonce<'info> {
+    #[account(address = store.admin @ MyOAppError::Unauthorized)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [STORE_SEED],
+        bump = store.bump,
+        constraint = params.receiver == store.key()
+    )]
+    pub store: Account<'info, OAppStore>,
+    #[account(
+        mut,
+        seeds = [NONCE_SEED, &params.receiver.as_ref(), &params.src_eid.to_be_bytes(), &params.sender],
+        bump = nonce_account.bump
+    )]
+    pub nonce_account: Account<'info, Nonce>,
+}
+
+impl SkipInboundNonce<'_> {
+    /// Skips a stuck message on the endpoint and moves the pathway past it. Remaining accounts
+    /// are the endpoint skip accounts.
+    pub fn apply(ctx: &mut Context<SkipInboundNonce>, params: &SkipInboundNonceParams) -> Result<()> {
+        let nonce_account = &mut ctx.accounts.nonce_account;
+        if nonce_account.ordered_nonce {
+            require!(
+                params.nonce == nonce_account.max_received_nonce + 1,
+                MyOAppError::InvalidNonce
+            );
+        }
+
+        let seeds: &[&[u8]] = &[STORE_SEED, &[ctx.accounts.store.bump]];
+        oapp::endpoint_cpi::skip_nonce(
+            ENDPOINT_ID,
+            ctx.remaining_accounts,
+            seeds,
+            SkipParams {
+                receiver: params.receiver,
+                src_eid: params.src_eid,
+                sender: params.sender,
+                nonce: params.nonce,
+            },
+        )?;
+
+        nonce_account.max_received_nonce = nonce_account.max_received_nonce.max(params.nonce);
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SkipInboundNonceParams {
+    pub receiver: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+}
@@ -0,0 +1,35 @@
+use crate::errors::MyOAppError;
+use crate::events::IdentityUnlinked;
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UnlinkIdentity<'info> {
+    #[account(mut)]
+    pub solana: Signer<'info>,
+    #[account(
+        mut,
+        close = solana,
+        seeds = [
+            IDENTITY_LINK_SEED,
+            &identity_link.universal.eid.to_be_bytes(),
+            &identity_link.universal.address
+        ],
+        bump = identity_link.bump,
+        has_one = solana @ MyOAppError::Unauthorized,
+    )]
+    pub identity_link: Account<'info, IdentityLink>,
+}
+
+impl<'info> UnlinkIdentity<'info> {
+    /// Removes a link. The address can then be linked again with a new proof.
+    pub fn apply(ctx: &mut Context<UnlinkIdentity>) -> Result<()> {
+        let link = &ctx.accounts.identity_link;
+        emit!(IdentityUnlinked {
+            eid: link.universal.eid,
+            address: link.universal.address,
+            solana: link.solana,
+        });
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+mod abi_codec;
 mod errors;
+mod events;
+mod handlers;
 mod instructions;
 mod msg_codec;
 mod state;
@@ -6,7 +9,7 @@ mod state;
 
 use anchor_lang::prelude::*;
 use instructions::*;
-use oapp::{endpoint::MessagingFee, LzReceiveParams};
+use oapp::{endpoint::MessagingFee, LzComposeParams, LzReceiveParams};
 use solana_helper::program_id_from_env;
 use state::*;
 
@@ -29,8 +32,28 @@ declare_id!(anchor_lang::solana_program::pubkey::Pubkey::new_from_array(program_
 )));
 
 const LZ_RECEIVE_TYPES_SEED: &[u8] = b"LzReceiveTypes";
+const LZ_COMPOSE_TYPES_SEED: &[u8] = b"LzComposeTypes";
 const STORE_SEED: &[u8] = b"Store";
 const PEER_SEED: &[u8] = b"Peer";
+const MESSAGE_TYPE_OPTIONS_SEED: &[u8] = b"MessageTypeOptions";
+const REMOTE_AUTHORITY_SEED: &[u8] = b"RemoteAuthority";
+const REMOTE_ADMIN_SEED: &[u8] = b"RemoteAdmin";
+const PROTOCOL_REGISTRY_SEED: &[u8] = b"ProtocolRegistry";
+const REMOTE_POSITION_SEED: &[u8] = b"RemotePosition";
+const POSITION_MIRROR_SEED: &[u8] = b"PositionMirror";
+const PROTOCOL_STATS_SEED: &[u8] = b"ProtocolStats";
+const PROPOSAL_SEED: &[u8] = b"Proposal";
+const PRICE_FEED_SEED: &[u8] = b"PriceFeed";
+const RECEIVED_MESSAGE_SEED: &[u8] = b"ReceivedMessage";
+const RECEIVED_MESSAGE_ID_SEED: &[u8] = b"ReceivedMessageId";
+const NONCE_SEED: &[u8] = b"Nonce";
+const GUARDIAN_SEED: &[u8] = b"Guardian";
+const OUTBOUND_MESSAGE_SEED: &[u8] = b"OutboundMessage";
+const DEPOSIT_ESCROW_SEED: &[u8] = b"DepositEscrow";
+const IDENTITY_LINK_SEED: &[u8] = b"IdentityLink";
+const PEER_CREDIT_SEED: &[u8] = b"PeerCredit";
+const VOTE_RECORD_SEED: &[u8] = b"VoteRecord";
+const PENDING_WITHDRAWAL_SEED: &[u8] = b"PendingWithdrawal";
 
 // Yield aggregator seeds - temporarily commented out
 // pub const YIELD_AGGREGATOR_SEED: &[u8] = b"YieldAggregator";
@@ -47,11 +70,51 @@ pub mod my_oapp {
         InitStore::apply(&mut ctx, &params)
     }
 
-    pub fn set_peer_config(
-        mut ctx: Context<SetPeerConfig>,
-        params: SetPeerConfigParams,
+    pub fn init_peer(mut ctx: Context<InitPeer>, params: InitPeerParams) -> Result<()> {
+        InitPeer::apply(&mut ctx, &params)
+    }
+
+    pub fn set_peer_address(
+        mut ctx: Context<SetPeerAddress>,
+        params: SetPeerAddressParams,
+    ) -> Result<()> {
+        SetPeerAddress::apply(&mut ctx, &params)
+    }
+
+    pub fn set_enforced_options(
+        mut ctx: Context<SetEnforcedOptions>,
+        params: SetEnforcedOptionsParams,
+    ) -> Result<()> {
+        SetEnforcedOptions::apply(&mut ctx, &params)
+    }
+
+    pub fn set_message_type_options(
+        mut ctx: Context<SetMessageTypeOptions>,
+        params: SetMessageTypeOptionsParams,
+    ) -> Result<()> {
+        SetMessageTypeOptions::apply(&mut ctx, &params)
+    }
+
+    pub fn close_peer(mut ctx: Context<ClosePeer>, params: ClosePeerParams) -> Result<()> {
+        ClosePeer::apply(&mut ctx, &params)
+    }
+
+    pub fn set_ordered_nonce(
+        mut ctx: Context<SetOrderedNonce>,
+        params: SetOrderedNonceParams,
+    ) -> Result<()> {
+        SetOrderedNonce::apply(&mut ctx, &params)
+    }
+
+    pub fn skip_inbound_nonce(
+        mut ctx: Context<SkipInboundNonce>,
+        params: SkipInboundNonceParams,
     ) -> Result<()> {
-        SetPeerConfig::apply(&mut ctx, &params)
+        SkipInboundNonce::apply(&mut ctx, &params)
+    }
+
+    pub fn next_nonce(ctx: Context<NextNonce>, params: NextNonceParams) -> Result<u64> {
+        NextNonce::apply(&ctx, &params)
     }
 
     pub fn quote_send(ctx: Context<QuoteSend>, params: QuoteSendParams) -> Result<MessagingFee> {
@@ -62,7 +125,83 @@ pub mod my_oapp {
         Send::apply(&mut ctx, &params)
     }
 
-    pub fn lz_receive(mut ctx: Context<LzReceive>, params: LzReceiveParams) -> Result<()> {
+    pub fn send_yield_message(
+        mut ctx: Context<SendYieldMessage>,
+        params: SendYieldMessageParams,
+    ) -> Result<()> {
+        SendYieldMessage::apply(&mut ctx, &params)
+    }
+
+    pub fn quote_yield_message(
+        ctx: Context<QuoteYieldMessage>,
+        params: QuoteYieldMessageParams,
+    ) -> Result<MessagingFee> {
+        QuoteYieldMessage::apply(&ctx, &params)
+    }
+
+    pub fn send_position_sync(
+        mut ctx: Context<SendPositionSync>,
+        params: SendPositionSyncParams,
+    ) -> Result<()> {
+        SendPositionSync::apply(&mut ctx, &params)
+    }
+
+    pub fn send_withdrawal_release(
+        mut ctx: Context<SendWithdrawalRelease>,
+        params: SendWithdrawalReleaseParams,
+    ) -> Result<()> {
+        SendWithdrawalRelease::apply(&mut ctx, &params)
+    }
+
+    pub fn send_deposit(mut ctx: Context<SendDeposit>, params: SendDepositParams) -> Result<()> {
+        SendDeposit::apply(&mut ctx, &params)
+    }
+
+    pub fn release_deposit_escrow(mut ctx: Context<ReleaseDepositEscrow>) -> Result<()> {
+        ReleaseDepositEscrow::apply(&mut ctx)
+    }
+
+    pub fn refund_expired_deposit(mut ctx: Context<RefundExpiredDeposit>) -> Result<()> {
+        RefundExpiredDeposit::apply(&mut ctx)
+    }
+
+    pub fn link_identity(mut ctx: Context<LinkIdentity>, params: LinkIdentityParams) -> Result<()> {
+        LinkIdentity::apply(&mut ctx, &params)
+    }
+
+    pub fn unlink_identity(mut ctx: Context<UnlinkIdentity>) -> Result<()> {
+        UnlinkIdentity::apply(&mut ctx)
+    }
+
+    pub fn set_guardian(mut ctx: Context<SetGuardian>, params: SetGuardianParams) -> Result<()> {
+        SetGuardian::apply(&mut ctx, &params)
+    }
+
+    pub fn set_peer_credit(
+        mut ctx: Context<SetPeerCredit>,
+        params: SetPeerCreditParams,
+    ) -> Result<()> {
+        SetPeerCredit::apply(&mut ctx, &params)
+    }
+
+    pub fn set_protocol_route(
+        mut ctx: Context<SetProtocolRoute>,
+        params: SetProtocolRouteParams,
+    ) -> Result<()> {
+        SetProtocolRoute::apply(&mut ctx, &params)
+    }
+
+    pub fn prune_received_messages<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, PruneReceivedMessages<'info>>,
+        params: PruneReceivedMessagesParams,
+    ) -> Result<()> {
+        PruneReceivedMessages::apply(&mut ctx, &params)
+    }
+
+    pub fn lz_receive<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, LzReceive<'info>>,
+        params: LzReceiveParams,
+    ) -> Result<()> {
         LzReceive::apply(&mut ctx, &params)
     }
 
@@ -73,6 +212,27 @@ pub mod my_oapp {
         LzReceiveTypes::apply(&ctx, &params)
     }
 
+    pub fn lz_compose<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, LzCompose<'info>>,
+        params: LzComposeParams,
+    ) -> Result<()> {
+        LzCompose::apply(&mut ctx, &params)
+    }
+
+    pub fn lz_compose_types(
+        ctx: Context<LzComposeTypes>,
+        params: LzComposeParams,
+    ) -> Result<Vec<oapp::endpoint_cpi::LzAccount>> {
+        LzComposeTypes::apply(&ctx, &params)
+    }
+
+    pub fn get_optimal_strategy(
+        ctx: Context<GetOptimalStrategy>,
+        params: GetOptimalStrategyParams,
+    ) -> Result<::yield_aggregator::strategy::Strategy> {
+        GetOptimalStrategy::apply(&ctx, &params)
+    }
+
     // ============================== Yield Aggregator Instructions ==============================
     // Temporarily commented out to fix compilation issues
     
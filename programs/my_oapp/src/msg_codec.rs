@@ -8,19 +8,38 @@ use std::str;
 // sending and receiving chains agree.
 // -----------------------------------------------------------------------------
 
-// Message layout:
+// Message layout (all integers big-endian):
 // Offset →
-// 0        1        5                     37       41                     41+N
-// |--------|--------|---------------------|--------|------------------------->
-// | 1 byte | 4 bytes|     32 bytes       | 4 bytes|     N bytes            |
-// |msg_type|version |    message_id      |  len   | serialized payload     |
-// |--------|--------|---------------------|--------|------------------------|
+// 0        1        5            37       45          53       57        61            61+N        61+N+M
+// |--------|--------|------------|--------|-----------|--------|---------|-------------|-----------|------->
+// | 1 byte | 4 bytes|  32 bytes  | 8 bytes| 8 bytes   | 4 bytes| 4 bytes |   N bytes   |  M bytes  | rest
+// |msg_type|version | message_id | nonce  | timestamp |  len   | cmp_len | ABI payload | compose   | signature
+// |--------|--------|------------|--------|-----------|--------|---------|-------------|-----------|------->
+//
+// The header has a fixed size so a receiver can read the type and version without decoding
+// the payload, and an EVM contract can slice it directly. The payload is ABI encoded (see
+// abi_codec.rs) so both chains share one format. A non-empty compose message defers the
+// payload to `lz_compose` (see below). Any bytes after it are the optional signature.
 
 pub const MSG_TYPE_OFFSET: usize = 0;
 pub const VERSION_OFFSET: usize = 1;
 pub const MESSAGE_ID_OFFSET: usize = 5;
-pub const LENGTH_OFFSET: usize = 37;
-pub const PAYLOAD_OFFSET: usize = 41;
+pub const NONCE_OFFSET: usize = 37;
+pub const TIMESTAMP_OFFSET: usize = 45;
+pub const LENGTH_OFFSET: usize = 53;
+pub const COMPOSE_LENGTH_OFFSET: usize = 57;
+pub const PAYLOAD_OFFSET: usize = 61;
+
+// Compose message layout: | 1 byte flags | rest reserved |
+pub const COMPOSE_FLAGS_OFFSET: usize = 0;
+// Request a PositionSync back to the source chain once the composed step has run. lz_compose
+// only emits PositionSyncRequested for send_position_sync to answer, as the send library
+// accounts of the reply can't be derived in lz_compose_types.
+pub const COMPOSE_REQUEST_SYNC: u8 = 1 << 0;
+
+// lz_receive forwards a composed message to lz_compose as | 4 byte src_eid | message |
+pub const FORWARDED_SRC_EID_OFFSET: usize = 0;
+pub const FORWARDED_MESSAGE_OFFSET: usize = 4;
 
 // Legacy string message offsets
 pub const STRING_LENGTH_OFFSET: usize = 0;
@@ -29,6 +48,29 @@ pub const STRING_PAYLOAD_OFFSET: usize = 32;
 // Current protocol version
 pub const PROTOCOL_VERSION: u32 = 1;
 
+// Message type of each YieldMessage variant, as sent in the header
+pub const DEPOSIT_REQUEST_TYPE: u8 = 1;
+pub const WITHDRAW_REQUEST_TYPE: u8 = 2;
+pub const REBALANCE_REQUEST_TYPE: u8 = 3;
+pub const YIELD_UPDATE_TYPE: u8 = 4;
+pub const POSITION_SYNC_TYPE: u8 = 5;
+pub const YIELD_DISTRIBUTION_TYPE: u8 = 6;
+pub const EMERGENCY_ACTION_TYPE: u8 = 7;
+pub const PROTOCOL_CONFIG_TYPE: u8 = 8;
+pub const GOVERNANCE_VOTE_TYPE: u8 = 9;
+pub const LIQUIDATION_NOTICE_TYPE: u8 = 10;
+pub const FEE_COLLECTION_TYPE: u8 = 11;
+pub const PRICE_UPDATE_TYPE: u8 = 12;
+pub const MESSAGE_ACK_TYPE: u8 = 13;
+pub const WITHDRAWAL_RELEASE_TYPE: u8 = 14;
+
+// Longest destination address a withdrawal can name, a Solana pubkey or a padded EVM address
+pub const MAX_DESTINATION_ADDRESS_LEN: usize = 32;
+
+// Accepted window for the header timestamp, in seconds
+pub const MAX_MESSAGE_AGE: i64 = 3600;
+pub const MAX_CLOCK_DRIFT: i64 = 300;
+
 #[error_code]
 pub enum MsgCodecError {
     #[msg("Buffer too short to contain the message header")]
@@ -55,7 +97,7 @@ pub enum MsgCodecError {
 pub enum YieldMessage {
     /// Cross-chain deposit request
     DepositRequest {
-        user: Pubkey,
+        user: [u8; 32], // Address on the user's chain, see UniversalAddress
         amount: u64,
         token_mint: Pubkey,
         target_protocol_id: u64,
@@ -67,19 +109,19 @@ pub enum YieldMessage {
     
     /// Cross-chain withdrawal request
     WithdrawRequest {
-        user: Pubkey,
+        user: [u8; 32], // Address on the user's chain, see UniversalAddress
         amount: u64,
         token_mint: Pubkey,
         protocol_id: u64,
-        target_chain_id: u32,
+        target_chain_id: u32, // Eid the assets go to
         destination_address: Vec<u8>,
-        withdraw_yield: bool,
+        withdraw_yield: bool, // Only the yield, leaving the principal in the position
         deadline: i64,
     },
     
     /// Cross-chain position rebalancing
     RebalanceRequest {
-        user: Pubkey,
+        user: [u8; 32], // Address on the user's chain, see UniversalAddress
         from_protocol_id: u64,
         to_protocol_id: u64,
         amount: u64,
@@ -102,7 +144,7 @@ pub enum YieldMessage {
     
     /// User position synchronization
     PositionSync {
-        user: Pubkey,
+        user: [u8; 32], // Address on the user's chain, see UniversalAddress
         protocol_id: u64,
         principal_amount: u64,
         yield_earned: u64,
@@ -142,7 +184,7 @@ pub enum YieldMessage {
     /// Cross-chain governance voting
     GovernanceVote {
         proposal_id: u64,
-        voter: Pubkey,
+        voter: [u8; 32], // Address on the user's chain, see UniversalAddress
         vote_weight: u64,
         vote_choice: bool, // true for yes, false for no
         voting_power: u64,
@@ -151,7 +193,7 @@ pub enum YieldMessage {
     
     /// Cross-chain liquidation notice
     LiquidationNotice {
-        user: Pubkey,
+        user: [u8; 32], // Address on the user's chain, see UniversalAddress
         protocol_id: u64,
         liquidated_amount: u64,
         liquidation_penalty: u64,
@@ -177,6 +219,24 @@ pub enum YieldMessage {
         timestamp: i64,
         oracle_source: String,
     },
+
+    /// Receipt for a message this chain received, so the sender can settle its outbound ledger
+    MessageAck {
+        nonce: u64, // Header nonce of the acknowledged message
+        message_id: [u8; 32],
+        success: bool,
+        timestamp: i64,
+    },
+
+    /// Return leg of a withdrawal redeemed on another chain, paid out here from custody
+    WithdrawalRelease {
+        user: [u8; 32], // Owner of the redeemed position, on the chain that requested it
+        amount: u64,
+        token_mint: Pubkey,
+        protocol_id: u64,
+        destination_address: Vec<u8>,
+        timestamp: i64,
+    },
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
@@ -212,6 +272,7 @@ pub struct MessageHeader {
     pub version: u32,
     pub message_id: [u8; 32],
     pub payload_length: u32,
+    pub compose_length: u32,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -220,34 +281,80 @@ pub struct CrossChainMessage {
     pub payload: YieldMessage,
     pub nonce: u64,
     pub timestamp: i64,
+    pub compose_msg: Option<Vec<u8>>,
     pub signature: Option<Vec<u8>>,
 }
 
+/// Guardian signature over the id of a privileged message. The guardian signs ahead of the
+/// send, so the header uses the timestamp it signed for instead of the send time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GuardianSignature {
+    pub timestamp: i64,
+    pub signature: [u8; 64],
+}
+
 impl YieldMessage {
-    /// Encode message with full header and metadata
-    pub fn encode_with_header(&self, message_id: [u8; 32], nonce: u64) -> Result<Vec<u8>> {
-        let payload_bytes = self.try_to_vec()
-            .map_err(|_| MsgCodecError::SerializationError)?;
-        
-        let header = MessageHeader {
-            msg_type: self.get_message_type(),
-            version: PROTOCOL_VERSION,
-            message_id,
-            payload_length: payload_bytes.len() as u32,
-        };
-        
-        let cross_chain_msg = CrossChainMessage {
-            header,
+    /// Message stamped with `nonce` and `timestamp`, its id derived from the content.
+    pub fn to_cross_chain(
+        &self,
+        nonce: u64,
+        timestamp: i64,
+        compose_msg: Option<Vec<u8>>,
+    ) -> CrossChainMessage {
+        let mut message = CrossChainMessage {
+            header: MessageHeader {
+                msg_type: self.get_message_type(),
+                version: PROTOCOL_VERSION,
+                message_id: [0u8; 32],
+                payload_length: 0,
+                compose_length: 0,
+            },
             payload: self.clone(),
             nonce,
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp,
+            compose_msg,
             signature: None,
         };
-        
-        cross_chain_msg.try_to_vec()
-            .map_err(|_| MsgCodecError::SerializationError.into())
+        message.header.message_id = message.generate_message_id();
+        message
     }
-    
+
+    /// User a message is about, recorded in the outbound ledger.
+    pub fn user(&self) -> Option<[u8; 32]> {
+        match self {
+            YieldMessage::DepositRequest { user, .. }
+            | YieldMessage::WithdrawRequest { user, .. }
+            | YieldMessage::RebalanceRequest { user, .. }
+            | YieldMessage::PositionSync { user, .. }
+            | YieldMessage::LiquidationNotice { user, .. }
+            | YieldMessage::WithdrawalRelease { user, .. } => Some(*user),
+            YieldMessage::GovernanceVote { voter, .. } => Some(*voter),
+            _ => None,
+        }
+    }
+
+    /// Token amount a message moves, recorded in the outbound ledger.
+    pub fn amount(&self) -> Option<u64> {
+        match self {
+            YieldMessage::DepositRequest { amount, .. }
+            | YieldMessage::WithdrawRequest { amount, .. }
+            | YieldMessage::RebalanceRequest { amount, .. }
+            | YieldMessage::WithdrawalRelease { amount, .. } => Some(*amount),
+            _ => None,
+        }
+    }
+
+    /// Account a user message acts for. Only that account may send it.
+    pub fn initiator(&self) -> Option<[u8; 32]> {
+        match self {
+            YieldMessage::DepositRequest { user, .. }
+            | YieldMessage::WithdrawRequest { user, .. }
+            | YieldMessage::RebalanceRequest { user, .. } => Some(*user),
+            YieldMessage::GovernanceVote { voter, .. } => Some(*voter),
+            _ => None,
+        }
+    }
+
     /// Encode just the payload (backward compatibility)
     pub fn encode(&self) -> Result<Vec<u8>> {
         self.try_to_vec()
@@ -256,12 +363,7 @@ impl YieldMessage {
     
     /// Decode message with header validation
     pub fn decode_with_header(data: &[u8]) -> Result<CrossChainMessage> {
-        if data.len() < PAYLOAD_OFFSET {
-            return Err(MsgCodecError::InvalidLength.into());
-        }
-        
-        CrossChainMessage::try_from_slice(data)
-            .map_err(|_| MsgCodecError::DeserializationError.into())
+        CrossChainMessage::decode(data)
     }
     
     /// Decode just the payload (backward compatibility)
@@ -273,18 +375,20 @@ impl YieldMessage {
     /// Get message type identifier
     pub fn get_message_type(&self) -> u8 {
         match self {
-            YieldMessage::DepositRequest { .. } => 1,
-            YieldMessage::WithdrawRequest { .. } => 2,
-            YieldMessage::RebalanceRequest { .. } => 3,
-            YieldMessage::YieldUpdate { .. } => 4,
-            YieldMessage::PositionSync { .. } => 5,
-            YieldMessage::YieldDistribution { .. } => 6,
-            YieldMessage::EmergencyAction { .. } => 7,
-            YieldMessage::ProtocolConfig { .. } => 8,
-            YieldMessage::GovernanceVote { .. } => 9,
-            YieldMessage::LiquidationNotice { .. } => 10,
-            YieldMessage::FeeCollection { .. } => 11,
-            YieldMessage::PriceUpdate { .. } => 12,
+            YieldMessage::DepositRequest { .. } => DEPOSIT_REQUEST_TYPE,
+            YieldMessage::WithdrawRequest { .. } => WITHDRAW_REQUEST_TYPE,
+            YieldMessage::RebalanceRequest { .. } => REBALANCE_REQUEST_TYPE,
+            YieldMessage::YieldUpdate { .. } => YIELD_UPDATE_TYPE,
+            YieldMessage::PositionSync { .. } => POSITION_SYNC_TYPE,
+            YieldMessage::YieldDistribution { .. } => YIELD_DISTRIBUTION_TYPE,
+            YieldMessage::EmergencyAction { .. } => EMERGENCY_ACTION_TYPE,
+            YieldMessage::ProtocolConfig { .. } => PROTOCOL_CONFIG_TYPE,
+            YieldMessage::GovernanceVote { .. } => GOVERNANCE_VOTE_TYPE,
+            YieldMessage::LiquidationNotice { .. } => LIQUIDATION_NOTICE_TYPE,
+            YieldMessage::FeeCollection { .. } => FEE_COLLECTION_TYPE,
+            YieldMessage::PriceUpdate { .. } => PRICE_UPDATE_TYPE,
+            YieldMessage::MessageAck { .. } => MESSAGE_ACK_TYPE,
+            YieldMessage::WithdrawalRelease { .. } => WITHDRAWAL_RELEASE_TYPE,
         }
     }
     
@@ -305,14 +409,22 @@ impl YieldMessage {
                     return Err(ProgramError::InvalidArgument.into());
                 }
             },
-            YieldMessage::WithdrawRequest { amount, deadline, .. } => {
+            YieldMessage::WithdrawRequest { amount, destination_address, deadline, .. } => {
                 if *amount == 0 {
                     return Err(ProgramError::InvalidArgument.into());
                 }
+                if destination_address.len() > MAX_DESTINATION_ADDRESS_LEN {
+                    return Err(MsgCodecError::InvalidPayload.into());
+                }
                 if *deadline < Clock::get()?.unix_timestamp {
                     return Err(ProgramError::InvalidArgument.into());
                 }
             },
+            YieldMessage::WithdrawalRelease { amount, .. } => {
+                if *amount == 0 {
+                    return Err(ProgramError::InvalidArgument.into());
+                }
+            },
             YieldMessage::RebalanceRequest { amount, min_output_amount, deadline, .. } => {
                 if *amount == 0 {
                     return Err(ProgramError::InvalidArgument.into());
@@ -343,40 +455,165 @@ impl YieldMessage {
     }
 }
 
+impl MessageHeader {
+    /// Reads the fixed-size header without touching the payload.
+    pub fn peek(data: &[u8]) -> std::result::Result<Self, MsgCodecError> {
+        if data.len() < PAYLOAD_OFFSET {
+            return Err(MsgCodecError::InvalidLength);
+        }
+        let mut message_id = [0u8; 32];
+        message_id.copy_from_slice(&data[MESSAGE_ID_OFFSET..NONCE_OFFSET]);
+        Ok(Self {
+            msg_type: data[MSG_TYPE_OFFSET],
+            version: u32::from_be_bytes(data[VERSION_OFFSET..MESSAGE_ID_OFFSET].try_into().unwrap()),
+            message_id,
+            payload_length: u32::from_be_bytes(
+                data[LENGTH_OFFSET..COMPOSE_LENGTH_OFFSET].try_into().unwrap(),
+            ),
+            compose_length: u32::from_be_bytes(
+                data[COMPOSE_LENGTH_OFFSET..PAYLOAD_OFFSET].try_into().unwrap(),
+            ),
+        })
+    }
+}
+
+/// Message type of an encoded message, read from the header.
+pub fn peek_message_type(data: &[u8]) -> std::result::Result<u8, MsgCodecError> {
+    Ok(MessageHeader::peek(data)?.msg_type)
+}
+
+/// Protocol version of an encoded message, read from the header.
+pub fn peek_version(data: &[u8]) -> std::result::Result<u32, MsgCodecError> {
+    Ok(MessageHeader::peek(data)?.version)
+}
+
+// A message that carries a compose message is only cleared and recorded by lz_receive, which
+// hands it to the endpoint compose queue. lz_compose then runs the payload in its own
+// transaction, and the compose flags pick the follow-up steps.
+
+/// Wraps a received message for `send_compose` so `lz_compose` knows where it came from.
+pub fn encode_forwarded(src_eid: u32, message: &[u8]) -> Vec<u8> {
+    let mut forwarded = Vec::with_capacity(FORWARDED_MESSAGE_OFFSET + message.len());
+    forwarded.extend_from_slice(&src_eid.to_be_bytes());
+    forwarded.extend_from_slice(message);
+    forwarded
+}
+
+/// Splits a forwarded message into its source endpoint id and the original message.
+pub fn decode_forwarded(forwarded: &[u8]) -> std::result::Result<(u32, &[u8]), MsgCodecError> {
+    if forwarded.len() < FORWARDED_MESSAGE_OFFSET {
+        return Err(MsgCodecError::InvalidLength);
+    }
+    let src_eid = u32::from_be_bytes(
+        forwarded[FORWARDED_SRC_EID_OFFSET..FORWARDED_MESSAGE_OFFSET].try_into().unwrap(),
+    );
+    Ok((src_eid, &forwarded[FORWARDED_MESSAGE_OFFSET..]))
+}
+
 impl CrossChainMessage {
+    /// Header fields at their fixed offsets, then the ABI payload, the compose message and the
+    /// signature. `payload_length` and `compose_length` are recomputed from the message.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let payload = self.payload.abi_encode();
+        let compose_msg = self.compose_msg.as_deref().unwrap_or_default();
+        let signature = self.signature.as_deref().unwrap_or_default();
+
+        let mut msg = Vec::with_capacity(
+            PAYLOAD_OFFSET + payload.len() + compose_msg.len() + signature.len(),
+        );
+        msg.push(self.header.msg_type);
+        msg.extend_from_slice(&self.header.version.to_be_bytes());
+        msg.extend_from_slice(&self.header.message_id);
+        msg.extend_from_slice(&self.nonce.to_be_bytes());
+        msg.extend_from_slice(&self.timestamp.to_be_bytes());
+        msg.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        msg.extend_from_slice(&(compose_msg.len() as u32).to_be_bytes());
+        msg.extend_from_slice(&payload);
+        msg.extend_from_slice(compose_msg);
+        msg.extend_from_slice(signature);
+        Ok(msg)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let header = MessageHeader::peek(data)?;
+        let payload_end = PAYLOAD_OFFSET
+            .checked_add(header.payload_length as usize)
+            .ok_or(MsgCodecError::InvalidLength)?;
+        let compose_end = payload_end
+            .checked_add(header.compose_length as usize)
+            .ok_or(MsgCodecError::InvalidLength)?;
+        if compose_end > data.len() {
+            return Err(MsgCodecError::BodyTooShort.into());
+        }
+
+        let payload = YieldMessage::abi_decode(header.msg_type, &data[PAYLOAD_OFFSET..payload_end])?;
+        let compose_msg = &data[payload_end..compose_end];
+        let signature = &data[compose_end..];
+        Ok(Self {
+            header,
+            payload,
+            nonce: u64::from_be_bytes(data[NONCE_OFFSET..TIMESTAMP_OFFSET].try_into().unwrap()),
+            timestamp: i64::from_be_bytes(data[TIMESTAMP_OFFSET..LENGTH_OFFSET].try_into().unwrap()),
+            compose_msg: (!compose_msg.is_empty()).then(|| compose_msg.to_vec()),
+            signature: (!signature.is_empty()).then(|| signature.to_vec()),
+        })
+    }
+
+    /// Flags byte of the compose message, zero when there is none.
+    pub fn compose_flags(&self) -> u8 {
+        self.compose_msg
+            .as_deref()
+            .and_then(|m| m.get(COMPOSE_FLAGS_OFFSET).copied())
+            .unwrap_or_default()
+    }
+
     /// Validate message header
     pub fn validate_header(&self) -> Result<()> {
+        self.validate_format()?;
+
+        // Validate timestamp (not too old, not too far in future)
+        let current_time = Clock::get()?.unix_timestamp;
+        if self.timestamp < current_time - MAX_MESSAGE_AGE
+            || self.timestamp > current_time + MAX_CLOCK_DRIFT
+        {
+            return Err(ProgramError::InvalidArgument.into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Version, type and id checks only. `lz_compose` uses this since a composed step may run
+    /// after the timestamp window, the delivery itself having been checked by `lz_receive`.
+    pub fn validate_format(&self) -> Result<()> {
         if self.header.version != PROTOCOL_VERSION {
             return Err(MsgCodecError::UnsupportedVersion.into());
         }
-        
+
         if self.header.msg_type != self.payload.get_message_type() {
             return Err(MsgCodecError::UnsupportedMessageType.into());
         }
-        
-        // Validate timestamp (not too old, not too far in future)
-        let current_time = Clock::get()?.unix_timestamp;
-        if self.timestamp < current_time - 3600 || self.timestamp > current_time + 300 {
-            return Err(ProgramError::InvalidArgument.into());
+
+        if self.header.message_id != self.generate_message_id() {
+            return Err(MsgCodecError::InvalidMessageId.into());
         }
-        
+
         Ok(())
     }
-    
-    /// Generate message ID from content
+
+    /// keccak256 of the header fields and the ABI payload as they go on the wire, leaving out
+    /// the id itself and the lengths. An EVM contract derives the same id with
+    /// `keccak256(abi.encodePacked(uint8(msgType), uint32(version), uint64(nonce), int64(timestamp), payload))`.
     pub fn generate_message_id(&self) -> [u8; 32] {
         use anchor_lang::solana_program::keccak;
-        
-        let mut hasher_input = Vec::new();
-        hasher_input.extend_from_slice(&self.nonce.to_le_bytes());
-        hasher_input.extend_from_slice(&self.timestamp.to_le_bytes());
-        hasher_input.extend_from_slice(&self.header.msg_type.to_le_bytes());
-        
-        if let Ok(payload_bytes) = self.payload.try_to_vec() {
-            hasher_input.extend_from_slice(&payload_bytes);
-        }
-        
-        keccak::hash(&hasher_input).to_bytes()
+
+        keccak::hashv(&[
+            &[self.header.msg_type],
+            &self.header.version.to_be_bytes(),
+            &self.nonce.to_be_bytes(),
+            &self.timestamp.to_be_bytes(),
+            &self.payload.abi_encode(),
+        ])
+        .to_bytes()
     }
 }
 
@@ -396,12 +633,14 @@ fn decode_string_len(buf: &[u8]) -> std::result::Result<usize, MsgCodecError> {
     Ok(u32::from_be_bytes(string_len_bytes[28..32].try_into().unwrap()) as usize)
 }
 
-/// Legacy string encoding (for backward compatibility with existing LayerZero messages)
-pub fn encode(string: &str) -> Vec<u8> {
+/// Legacy string encoding (for backward compatibility with existing LayerZero messages).
+/// A compose message is appended after the string.
+pub fn encode(string: &str, compose_msg: Option<&[u8]>) -> Vec<u8> {
     let string_bytes = string.as_bytes();
     let mut msg = Vec::with_capacity(
         STRING_PAYLOAD_OFFSET +    // header length
-        string_bytes.len()         // string bytes
+        string_bytes.len() +       // string bytes
+        compose_msg.map(|m| m.len()).unwrap_or(0) // optional tail
     );
 
     // 4 byte length stored at the end of the 32 byte header
@@ -411,6 +650,11 @@ pub fn encode(string: &str) -> Vec<u8> {
     // string
     msg.extend_from_slice(string_bytes);
 
+    // optional tail
+    if let Some(tail) = compose_msg {
+        msg.extend_from_slice(tail);
+    }
+
     msg
 }
 
@@ -448,8 +692,12 @@ pub fn is_protocol_message(msg_type: u8) -> bool {
     matches!(msg_type, 4 | 6 | 7 | 8 | 11) // YieldUpdate, Distribution, Emergency, Config, Fee
 }
 
+pub fn is_privileged_message(msg_type: u8) -> bool {
+    matches!(msg_type, 4 | 7 | 8) // YieldUpdate, Emergency, Config; guardian signed when one is set
+}
+
 pub fn is_system_message(msg_type: u8) -> bool {
-    matches!(msg_type, 5 | 10 | 12) // PositionSync, Liquidation, PriceUpdate
+    matches!(msg_type, 5 | 10 | 12 | 13) // PositionSync, Liquidation, PriceUpdate, Ack
 }
 
 #[cfg(test)]
@@ -459,15 +707,19 @@ mod tests {
     #[test]
     fn test_legacy_string_encoding() {
         let test_string = "Hello, World!";
-        let encoded = encode(test_string);
+        let encoded = encode(test_string, None);
         let decoded = decode(&encoded).unwrap();
         assert_eq!(test_string, decoded);
+
+        let composed = encode(test_string, Some(&[1, 2, 3]));
+        assert_eq!(decode(&composed).unwrap(), test_string);
+        assert_eq!(&composed[encoded.len()..], &[1, 2, 3]);
     }
     
     #[test]
     fn test_message_encoding_decoding() {
         let message = YieldMessage::DepositRequest {
-            user: Pubkey::new_unique(),
+            user: Pubkey::new_unique().to_bytes(),
             amount: 1000000,
             token_mint: Pubkey::new_unique(),
             target_protocol_id: 1,
@@ -482,4 +734,115 @@ mod tests {
         
         assert_eq!(message, decoded);
     }
+
+    fn cross_chain_message(
+        compose_msg: Option<Vec<u8>>,
+        signature: Option<Vec<u8>>,
+    ) -> CrossChainMessage {
+        let payload = YieldMessage::PriceUpdate {
+            token_mint: Pubkey::new_unique(),
+            price: 42_000_000,
+            confidence: 100,
+            timestamp: 1_700_000_000,
+            oracle_source: "pyth".to_string(),
+        };
+        CrossChainMessage {
+            header: MessageHeader {
+                msg_type: payload.get_message_type(),
+                version: PROTOCOL_VERSION,
+                message_id: [7u8; 32],
+                payload_length: 0,
+                compose_length: 0,
+            },
+            payload,
+            nonce: 3,
+            timestamp: 1_700_000_001,
+            compose_msg,
+            signature,
+        }
+    }
+
+    #[test]
+    fn test_header_is_at_fixed_offsets() {
+        let message = cross_chain_message(None, None);
+        let encoded = message.encode().unwrap();
+        let payload = message.payload.abi_encode();
+
+        assert_eq!(encoded.len(), PAYLOAD_OFFSET + payload.len());
+        assert_eq!(peek_message_type(&encoded).unwrap(), 12);
+        assert_eq!(peek_version(&encoded).unwrap(), PROTOCOL_VERSION);
+        assert_eq!(&encoded[MESSAGE_ID_OFFSET..NONCE_OFFSET], &[7u8; 32]);
+        assert_eq!(&encoded[NONCE_OFFSET..TIMESTAMP_OFFSET], &3u64.to_be_bytes());
+        assert_eq!(
+            &encoded[LENGTH_OFFSET..COMPOSE_LENGTH_OFFSET],
+            &(payload.len() as u32).to_be_bytes()
+        );
+        assert_eq!(&encoded[COMPOSE_LENGTH_OFFSET..PAYLOAD_OFFSET], &0u32.to_be_bytes());
+        assert_eq!(&encoded[PAYLOAD_OFFSET..], &payload[..]);
+    }
+
+    #[test]
+    fn test_message_id_matches_evm_vector() {
+        // Shared with test/foundry/YieldMsgCodec.t.sol
+        let payload = YieldMessage::ProtocolConfig {
+            protocol_id: 1,
+            config_type: ProtocolConfigType::YieldRate,
+            config_data: 1_500u64.to_be_bytes().to_vec(),
+            effective_timestamp: 1_700_000_000,
+        };
+        let message = payload.to_cross_chain(7, 1_700_000_000, None);
+        let expected = "2a0772f1e648cc32eff4373bad767edb7648f3b7a8c12121c574d910a1832eb6";
+        let id: String = message.header.message_id.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(id, expected);
+
+        // The id covers the payload but not itself
+        let mut changed = message.clone();
+        changed.nonce += 1;
+        assert_ne!(changed.generate_message_id(), message.header.message_id);
+        changed = message.clone();
+        changed.header.message_id = [0u8; 32];
+        assert_eq!(changed.generate_message_id(), message.header.message_id);
+
+        assert!(message.validate_format().is_ok());
+        assert!(changed.validate_format().is_err());
+    }
+
+    #[test]
+    fn test_cross_chain_message_round_trip() {
+        for compose_msg in [None, Some(vec![COMPOSE_REQUEST_SYNC])] {
+            for signature in [None, Some(vec![9u8; 64])] {
+                let message = cross_chain_message(compose_msg.clone(), signature);
+                let decoded = CrossChainMessage::decode(&message.encode().unwrap()).unwrap();
+                assert_eq!(decoded.payload, message.payload);
+                assert_eq!(decoded.header.message_id, message.header.message_id);
+                assert_eq!(decoded.nonce, message.nonce);
+                assert_eq!(decoded.timestamp, message.timestamp);
+                assert_eq!(decoded.compose_msg, message.compose_msg);
+                assert_eq!(decoded.signature, message.signature);
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncated_message_is_rejected() {
+        let encoded = cross_chain_message(None, None).encode().unwrap();
+        assert!(peek_message_type(&encoded[..PAYLOAD_OFFSET - 1]).is_err());
+        assert!(CrossChainMessage::decode(&encoded[..encoded.len() - 1]).is_err());
+
+        let composed = cross_chain_message(Some(vec![COMPOSE_REQUEST_SYNC]), None).encode().unwrap();
+        assert!(CrossChainMessage::decode(&composed[..composed.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_forwarded_message_round_trip() {
+        let message = cross_chain_message(Some(vec![COMPOSE_REQUEST_SYNC]), None).encode().unwrap();
+        let forwarded = encode_forwarded(30101, &message);
+        let (src_eid, original) = decode_forwarded(&forwarded).unwrap();
+        assert_eq!(src_eid, 30101);
+        assert_eq!(original, &message[..]);
+
+        let decoded = CrossChainMessage::decode(original).unwrap();
+        assert_eq!(decoded.compose_flags() & COMPOSE_REQUEST_SYNC, COMPOSE_REQUEST_SYNC);
+        assert!(decode_forwarded(&forwarded[..3]).is_err());
+    }
 }
\ No newline at end of file
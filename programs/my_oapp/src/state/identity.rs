@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::secp256k1_recover::secp256k1_recover;
+
+pub const EVM_ADDRESS_LEN: usize = 20;
+
+/// Prefix of the message an EVM account signs to link itself to a Solana account.
+pub const IDENTITY_LINK_DOMAIN: &[u8] = b"YieldOApp identity link";
+
+/// Account on any chain: its eid and its address as 32 bytes. Messages carry user addresses
+/// as `bytes32`, so an EVM address is left-padded with zeros, as `bytes32(uint256(uint160(a)))`
+/// does in Solidity, and a Solana pubkey is used as is.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct UniversalAddress {
+    pub eid: u32,
+    pub address: [u8; 32],
+}
+
+impl UniversalAddress {
+    pub fn new(eid: u32, address: [u8; 32]) -> Self {
+        Self { eid, address }
+    }
+
+    pub fn from_evm(eid: u32, address: [u8; EVM_ADDRESS_LEN]) -> Self {
+        let mut padded = [0u8; 32];
+        padded[32 - EVM_ADDRESS_LEN..].copy_from_slice(&address);
+        Self { eid, address: padded }
+    }
+}
+
+/// Links an address on another chain to the Solana account that may act for it, proven by
+/// a signature of the other account over `link_message`. Keyed by the universal address.
+#[account]
+#[derive(InitSpace)]
+pub struct IdentityLink {
+    pub universal: UniversalAddress,
+    pub solana: Pubkey,
+    pub linked_at: i64,
+    pub bump: u8,
+}
+
+impl IdentityLink {
+    /// Bytes the EVM account signs with `personal_sign`. They name this program, so the proof
+    /// can't be reused to link the address anywhere else.
+    pub fn link_message(universal: &UniversalAddress, solana: &Pubkey) -> Vec<u8> {
+        [
+            IDENTITY_LINK_DOMAIN,
+            crate::ID.as_ref(),
+            &universal.eid.to_be_bytes(),
+            &universal.address,
+            solana.as_ref(),
+        ]
+        .concat()
+    }
+}
+
+/// Address of the EVM account whose `personal_sign` signature over `message` is `signature`,
+/// if it recovers. `recovery_id` may be given as 0/1 or as Ethereum's 27/28.
+pub fn evm_signer(message: &[u8], signature: &[u8; 64], recovery_id: u8) -> Option<[u8; EVM_ADDRESS_LEN]> {
+    let recovery_id = if recovery_id >= 27 { recovery_id - 27 } else { recovery_id };
+    let prefix: &[u8] = b"\x19Ethereum Signed Message:\n";
+    let hash = keccak::hashv(&[prefix, message.len().to_string().as_bytes(), message]);
+    let key = secp256k1_recover(&hash.0, recovery_id, signature).ok()?;
+    keccak::hash(&key.to_bytes()).0[32 - EVM_ADDRESS_LEN..].try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evm_address_is_left_padded() {
+        let evm = [0xabu8; EVM_ADDRESS_LEN];
+        let universal = UniversalAddress::from_evm(40161, evm);
+        assert_eq!(universal.eid, 40161);
+        assert_eq!(universal.address[..12], [0u8; 12]);
+        assert_eq!(universal.address[12..], evm);
+    }
+
+    #[test]
+    fn test_link_message_names_both_accounts() {
+        let universal = UniversalAddress::from_evm(40161, [0xab; EVM_ADDRESS_LEN]);
+        let solana = Pubkey::new_unique();
+        let message = IdentityLink::link_message(&universal, &solana);
+        assert!(message.starts_with(IDENTITY_LINK_DOMAIN));
+        assert!(message.ends_with(solana.as_ref()));
+        assert_ne!(message, IdentityLink::link_message(&universal, &Pubkey::new_unique()));
+    }
+}
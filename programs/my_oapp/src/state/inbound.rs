@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+/// Marks a delivered message as executed, so a second delivery is rejected. Each delivery
+/// creates two, one keyed by `(src_eid, guid)` and one by `(src_eid, message_id)`.
+#[account]
+#[derive(InitSpace)]
+pub struct ReceivedMessage {
+    pub src_eid: u32,
+    pub guid: [u8; 32],
+    pub message_id: [u8; 32],
+    pub nonce: u64,
+    pub timestamp: i64, // Header timestamp; once it is older than MAX_MESSAGE_AGE a replay fails validation anyway
+    pub received_at: i64,
+    pub bump: u8,
+}
+
+/// Inbound nonce tracking for one `(store, src_eid, sender)` pathway. The executor reads it
+/// through `next_nonce` to decide whether a message can be delivered yet.
+#[account]
+#[derive(InitSpace)]
+pub struct Nonce {
+    pub max_received_nonce: u64,
+    pub ordered_nonce: bool, // Deliver strictly in nonce order on this pathway
+    pub bump: u8,
+}
+
+/// Key that co-signs privileged messages. While one is set, `YieldUpdate`, `EmergencyAction`
+/// and `ProtocolConfig` only execute with its ed25519 signature over the message id, so a
+/// compromised peer alone can't change rates or config.
+#[account]
+#[derive(InitSpace)]
+pub struct GuardianConfig {
+    pub guardian: Option<Pubkey>,
+    pub bump: u8,
+}
@@ -2,6 +2,17 @@ pub mod yield_aggregator;
 pub mod oapp_state;
 pub mod store;
 pub mod peer_config;
+pub mod remote;
+pub mod inbound;
+pub mod outbound;
+pub mod identity;
 
 // Use explicit imports to avoid ambiguity and unused warnings
-pub use oapp_state::{EnforcedOptions, Store as OAppStore, PeerConfig as OAppPeerConfig};
\ No newline at end of file
+pub use oapp_state::{
+    EnforcedOptions, MessageTypeOptions, PeerConfig as OAppPeerConfig, Store as OAppStore,
+};
+pub use remote::*;
+pub use inbound::*;
+pub use outbound::*;
+pub use identity::*;
+pub use store::{LzComposeTypesAccounts, LzReceiveTypesAccounts};
@@ -14,6 +14,17 @@ pub struct PeerConfig {
     pub peer_address: [u8; 32],
     pub bump: u8,
     pub enforced_options: EnforcedOptions,
+    pub outbound_nonce: u64, // Last nonce stamped on a YieldMessage sent to this peer
+}
+
+/// Enforced options for one message type on a pathway, keyed by `(store, eid, msg_type)` like
+/// `OAppOptionsType3` on EVM. They replace the peer's options for messages of that type.
+#[account]
+pub struct MessageTypeOptions {
+    pub eid: u32,
+    pub msg_type: u8,
+    pub enforced_options: EnforcedOptions,
+    pub bump: u8,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -23,18 +34,34 @@ pub struct EnforcedOptions {
 }
 
 impl EnforcedOptions {
+    /// Space reserved for the options in `PeerConfig`, including both length prefixes
+    pub const SIZE: usize = 200;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            4 + self.send.len() + 4 + self.send_and_call.len() <= Self::SIZE,
+            crate::errors::MyOAppError::EnforcedOptionsTooLarge
+        );
+        Ok(())
+    }
+
+    /// `send_and_call` options cover the extra gas of `lz_compose`, so they apply whenever a
+    /// compose message is sent.
+    pub fn get_enforced_options(&self, compose_msg: &Option<Vec<u8>>) -> &[u8] {
+        if compose_msg.is_none() {
+            &self.send
+        } else {
+            &self.send_and_call
+        }
+    }
+
     pub fn combine_options(
         &self,
-        extra_options: &Option<Vec<u8>>,
+        compose_msg: &Option<Vec<u8>>,
         user_options: &[u8],
     ) -> Result<Vec<u8>> {
-        let mut combined_options = self.send.clone();
-        
-        // Add extra options if provided
-        if let Some(extra) = extra_options {
-            combined_options.extend_from_slice(extra);
-        }
-        
+        let mut combined_options = self.get_enforced_options(compose_msg).to_vec();
+
         // Add user options
         combined_options.extend_from_slice(user_options);
         
@@ -46,6 +73,21 @@ impl Store {
     pub const SIZE: usize = 8 + 1 + 32 + 1 + 32 + 200; // discriminator + version + admin + bump + endpoint_program + string
 }
 
+impl MessageTypeOptions {
+    pub const SIZE: usize = 8 + 4 + 1 + EnforcedOptions::SIZE + 1; // discriminator + eid + msg_type + enforced_options + bump
+
+    /// Options for a message sent to `peer`: the ones set for its type, else the peer's.
+    pub fn select<'a>(
+        type_options: &'a Option<Account<MessageTypeOptions>>,
+        peer: &'a PeerConfig,
+    ) -> &'a EnforcedOptions {
+        match type_options {
+            Some(type_options) => &type_options.enforced_options,
+            None => &peer.enforced_options,
+        }
+    }
+}
+
 impl PeerConfig {
-    pub const SIZE: usize = 8 + 32 + 1 + 200; // discriminator + peer_address + bump + enforced_options
+    pub const SIZE: usize = 8 + 32 + 1 + EnforcedOptions::SIZE + 8; // discriminator + peer_address + bump + enforced_options + outbound_nonce
 }
\ No newline at end of file
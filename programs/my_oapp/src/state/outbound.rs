@@ -0,0 +1,172 @@
+use crate::errors::MyOAppError;
+use crate::msg_codec::{CrossChainMessage, MAX_CLOCK_DRIFT, MAX_MESSAGE_AGE};
+use anchor_lang::prelude::*;
+use oapp::endpoint::MessagingReceipt;
+
+/// Time after a deposit's deadline before it can be refunded without a failure ack. The
+/// destination only executes the deposit up to its deadline and acks it right away, and
+/// `lz_receive` rejects an ack whose header is older than `MAX_MESSAGE_AGE`, so no success ack
+/// can arrive past this window. Drift is allowed for on both chains.
+pub const DEPOSIT_REFUND_GRACE_PERIOD: i64 = MAX_MESSAGE_AGE + 2 * MAX_CLOCK_DRIFT;
+
+/// Record of a `YieldMessage` this OApp sent, keyed by `(dst_eid, nonce)` with the header
+/// nonce. The destination settles it with a `MessageAck`.
+#[account]
+#[derive(InitSpace)]
+pub struct OutboundMessage {
+    pub dst_eid: u32,
+    pub nonce: u64,
+    pub guid: [u8; 32], // Endpoint guid, for looking the packet up in LayerZero Scan
+    pub message_id: [u8; 32],
+    pub msg_type: u8,
+    pub user: [u8; 32], // Zero when the message isn't about a user
+    pub amount: u64,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+    pub status: OutboundStatus,
+    pub sent_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl OutboundMessage {
+    /// Entry for `message`, just sent to `dst_eid` with `receipt`.
+    pub fn sent(
+        dst_eid: u32,
+        message: &CrossChainMessage,
+        receipt: &MessagingReceipt,
+        sent_at: i64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            dst_eid,
+            nonce: message.nonce,
+            guid: receipt.guid,
+            message_id: message.header.message_id,
+            msg_type: message.header.msg_type,
+            user: message.payload.user().unwrap_or_default(),
+            amount: message.payload.amount().unwrap_or_default(),
+            native_fee: receipt.fee.native_fee,
+            lz_token_fee: receipt.fee.lz_token_fee,
+            status: OutboundStatus::Sent,
+            sent_at,
+            updated_at: sent_at,
+            bump,
+        }
+    }
+
+    /// Applies the destination's ack, if it is for this message. A success ack for a deposit
+    /// that was already refunded is kept as `AcknowledgedAfterRefund`, since the destination
+    /// then holds a position that nothing backs.
+    pub fn settle(&mut self, message_id: &[u8; 32], success: bool, now: i64) {
+        if self.message_id != *message_id {
+            return;
+        }
+        self.status = match (self.status, success) {
+            (OutboundStatus::Sent, true) => OutboundStatus::Acknowledged,
+            (OutboundStatus::Sent, false) => OutboundStatus::Failed,
+            (OutboundStatus::Expired, true) => OutboundStatus::AcknowledgedAfterRefund,
+            _ => return,
+        };
+        self.updated_at = now;
+    }
+
+    /// Marks a deposit as refunded. A failed deposit can be refunded at any time, one without
+    /// an ack only once `DEPOSIT_REFUND_GRACE_PERIOD` has passed after its deadline.
+    pub fn expire(&mut self, deadline: i64, now: i64) -> Result<()> {
+        match self.status {
+            OutboundStatus::Sent => {
+                let refundable_at = deadline.saturating_add(DEPOSIT_REFUND_GRACE_PERIOD);
+                require!(now > refundable_at, MyOAppError::DepositNotExpired);
+            }
+            OutboundStatus::Failed | OutboundStatus::Expired => {}
+            OutboundStatus::Acknowledged | OutboundStatus::AcknowledgedAfterRefund => {
+                return err!(MyOAppError::DepositAlreadyAcknowledged)
+            }
+        }
+        self.status = OutboundStatus::Expired;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum OutboundStatus {
+    Sent,
+    Acknowledged,            // Executed on the destination
+    Failed,                  // Delivered, but the destination reported that it could not execute it
+    Expired,                 // Refunded after a failure ack, or past its deadline without an ack
+    AcknowledgedAfterRefund, // Executed on the destination after the deposit was refunded
+}
+
+/// Tokens a user locked for a `DepositRequest` to another chain, keyed like the ledger entry
+/// of the message. They sit in the escrow's associated token account until the deposit is
+/// acknowledged and released to the remote authority, or refunded to the user.
+#[account]
+#[derive(InitSpace)]
+pub struct DepositEscrow {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub dst_eid: u32,
+    pub nonce: u64,
+    pub deadline: i64, // Deadline of the DepositRequest, after which it can be refunded
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEADLINE: i64 = 1_700_000_000;
+
+    fn entry(status: OutboundStatus) -> OutboundMessage {
+        OutboundMessage {
+            dst_eid: 30101,
+            nonce: 1,
+            guid: [1; 32],
+            message_id: [2; 32],
+            msg_type: 1,
+            user: [0; 32],
+            amount: 100,
+            native_fee: 0,
+            lz_token_fee: 0,
+            status,
+            sent_at: DEADLINE - 60,
+            updated_at: DEADLINE - 60,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_unacked_deposit_refunds_after_grace_period() {
+        let mut sent = entry(OutboundStatus::Sent);
+        assert!(sent.expire(DEADLINE, DEADLINE + 1).is_err());
+        assert!(sent.expire(DEADLINE, DEADLINE + DEPOSIT_REFUND_GRACE_PERIOD).is_err());
+        sent.expire(DEADLINE, DEADLINE + DEPOSIT_REFUND_GRACE_PERIOD + 1).unwrap();
+        assert_eq!(sent.status, OutboundStatus::Expired);
+
+        let mut failed = entry(OutboundStatus::Failed);
+        failed.expire(DEADLINE, DEADLINE - 30).unwrap();
+        assert_eq!(failed.status, OutboundStatus::Expired);
+
+        assert!(entry(OutboundStatus::Acknowledged).expire(DEADLINE, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_late_ack_of_refunded_deposit_is_kept() {
+        let mut refunded = entry(OutboundStatus::Expired);
+        refunded.settle(&[2; 32], true, DEADLINE + 10);
+        assert_eq!(refunded.status, OutboundStatus::AcknowledgedAfterRefund);
+        assert_eq!(refunded.updated_at, DEADLINE + 10);
+        assert!(refunded.expire(DEADLINE, i64::MAX).is_err());
+
+        let mut sent = entry(OutboundStatus::Sent);
+        sent.settle(&[3; 32], true, DEADLINE);
+        assert_eq!(sent.status, OutboundStatus::Sent);
+        sent.settle(&[2; 32], false, DEADLINE);
+        assert_eq!(sent.status, OutboundStatus::Failed);
+        sent.settle(&[2; 32], true, DEADLINE);
+        assert_eq!(sent.status, OutboundStatus::Failed);
+    }
+}
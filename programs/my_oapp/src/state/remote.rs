@@ -0,0 +1,263 @@
+use super::UniversalAddress;
+use crate::msg_codec::{YieldMessage, MAX_DESTINATION_ADDRESS_LEN};
+use anchor_lang::prelude::*;
+
+pub const MAX_PROTOCOL_ROUTES: usize = 16;
+pub const MAX_ORACLE_SOURCE_LEN: usize = 32;
+
+/// Maps the `protocol_id`s used in cross-chain messages to yield-aggregator protocols.
+/// Only the name and mint are stored; every other account of a protocol derives from them.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolRegistry {
+    #[max_len(MAX_PROTOCOL_ROUTES)]
+    pub routes: Vec<ProtocolRoute>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, InitSpace)]
+pub struct ProtocolRoute {
+    pub protocol_id: u64,
+    #[max_len(32)]
+    pub name: String,
+    pub mint: Pubkey,
+}
+
+impl ProtocolRegistry {
+    /// Reads the registry for the `*_types` instructions, where it is unchecked so that
+    /// messages can still be resolved before the first route is set.
+    pub fn load_unchecked(info: &AccountInfo) -> Result<Self> {
+        if info.data_is_empty() {
+            return Ok(Self { routes: Vec::new(), bump: 0 });
+        }
+        Self::try_deserialize(&mut &info.try_borrow_data()?[..])
+    }
+
+    pub fn route(&self, protocol_id: u64) -> Option<&ProtocolRoute> {
+        self.routes.iter().find(|r| r.protocol_id == protocol_id)
+    }
+}
+
+/// Shares the remote authority holds in a local vault on behalf of a user on another chain,
+/// keyed by the user's universal address.
+#[account]
+#[derive(InitSpace)]
+pub struct RemotePosition {
+    pub owner: UniversalAddress,
+    pub linked: Pubkey, // Solana account linked to the owner when last used, default if none
+    pub protocol_id: u64,
+    pub shares: u64,
+    pub principal: u64,
+    pub pending_withdrawal: u64, // Redeemed assets waiting in remote custody to be released
+    pub last_activity: i64,
+    pub bump: u8,
+}
+
+impl RemotePosition {
+    /// Removes `shares` and returns the principal released with them, rounded up like
+    /// `UserPosition::debit` on the aggregator.
+    pub fn debit(&mut self, shares: u64) -> Option<u64> {
+        if shares > self.shares {
+            return None;
+        }
+        let principal = if shares == self.shares {
+            self.principal
+        } else {
+            let scaled = self.principal as u128 * shares as u128;
+            let released = scaled.div_ceil(self.shares as u128);
+            (released as u64).min(self.principal)
+        };
+        self.principal -= principal;
+        self.shares -= shares;
+        Some(principal)
+    }
+
+    /// Removes `shares` redeemed for yield only, which leaves the principal as it is.
+    pub fn debit_yield(&mut self, shares: u64) -> Option<()> {
+        self.shares = self.shares.checked_sub(shares)?;
+        Some(())
+    }
+}
+
+/// Redeemed assets of a remote position waiting in custody for the return leg to another
+/// chain, keyed by the position, `dst_eid` and `destination_address`. Withdrawals to the same
+/// destination add up until `send_withdrawal_release` sends them as one `WithdrawalRelease`.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingWithdrawal {
+    pub position: Pubkey,
+    pub dst_eid: u32,
+    #[max_len(MAX_DESTINATION_ADDRESS_LEN)]
+    pub destination_address: Vec<u8>,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+impl PendingWithdrawal {
+    /// Message that pays the withdrawal out on `dst_eid`.
+    pub fn release_message(&self, position: &RemotePosition, timestamp: i64) -> YieldMessage {
+        YieldMessage::WithdrawalRelease {
+            user: position.owner.address,
+            amount: self.amount,
+            token_mint: self.token_mint,
+            protocol_id: position.protocol_id,
+            destination_address: self.destination_address.clone(),
+            timestamp,
+        }
+    }
+}
+
+/// Custody liquidity that deposits from one peer chain may draw on, set by the admin to what
+/// that chain has bridged in. Deposits use it up and principal withdrawn by the chain's users
+/// frees it again, so a peer can't spend liquidity that backs anyone else.
+#[account]
+#[derive(InitSpace)]
+pub struct PeerCredit {
+    pub eid: u32,
+    pub limit: u64,
+    pub used: u64,
+    pub bump: u8,
+}
+
+impl PeerCredit {
+    /// Takes `amount` from the credit line, or `None` if it would exceed the limit.
+    pub fn draw(&mut self, amount: u64) -> Option<()> {
+        self.used = self.used.checked_add(amount).filter(|used| *used <= self.limit)?;
+        Some(())
+    }
+
+    pub fn repay(&mut self, amount: u64) {
+        self.used = self.used.saturating_sub(amount);
+    }
+}
+
+/// Last reported state of a user's position on another chain.
+#[account]
+#[derive(InitSpace)]
+pub struct PositionMirror {
+    pub src_eid: u32,
+    pub user: [u8; 32],
+    pub protocol_id: u64,
+    pub principal: u64,
+    pub yield_earned: u64,
+    pub shares: u64,
+    pub position_health: u8,
+    pub liquidated_amount: u64,
+    pub last_compound_time: i64,
+    pub sync_timestamp: i64,
+    pub bump: u8,
+}
+
+/// Running totals for a protocol on another chain, from distribution, fee and liquidation reports.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolStats {
+    pub src_eid: u32,
+    pub protocol_id: u64,
+    pub total_yield_distributed: u64,
+    pub last_distribution_rate: u64,
+    pub last_distribution: i64,
+    pub fees_collected: [u64; 5], // Indexed by msg_codec::FeeType
+    pub total_liquidated: u64,
+    pub total_liquidation_penalty: u64,
+    pub bump: u8,
+}
+
+/// Cross-chain vote totals for a governance proposal.
+#[account]
+#[derive(InitSpace)]
+pub struct ProposalTally {
+    pub proposal_id: u64,
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    pub votes: u64,
+    pub last_vote: i64,
+    pub bump: u8,
+}
+
+/// A voter's counted vote on a proposal. Its existence is what stops the voter being counted
+/// twice.
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub proposal_id: u64,
+    pub voter: UniversalAddress,
+    pub weight: u64,
+    pub vote_choice: bool,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+/// Latest oracle price relayed for a token.
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    pub token_mint: Pubkey,
+    pub price: u64,
+    pub confidence: u64,
+    pub timestamp: i64,
+    pub src_eid: u32,
+    #[max_len(MAX_ORACLE_SOURCE_LEN)]
+    pub oracle_source: String,
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_credit_bounds_deposits() {
+        let mut credit = PeerCredit { eid: 40161, limit: 1_000, used: 0, bump: 0 };
+        assert!(credit.draw(600).is_some());
+        assert!(credit.draw(401).is_none());
+        assert_eq!(credit.used, 600);
+        assert!(credit.draw(400).is_some());
+        assert!(credit.draw(u64::MAX).is_none());
+
+        // Withdrawn principal frees the line again
+        credit.repay(300);
+        assert!(credit.draw(300).is_some());
+        credit.repay(u64::MAX);
+        assert_eq!(credit.used, 0);
+    }
+
+    #[test]
+    fn test_pending_withdrawal_releases_to_its_destination() {
+        let owner = UniversalAddress::from_evm(40161, [0xab; 20]);
+        let position = RemotePosition {
+            owner,
+            linked: Pubkey::default(),
+            protocol_id: 2,
+            shares: 0,
+            principal: 0,
+            pending_withdrawal: 700,
+            last_activity: 0,
+            bump: 0,
+        };
+        let pending = PendingWithdrawal {
+            position: Pubkey::new_unique(),
+            dst_eid: 40231,
+            destination_address: vec![0xcd; 20],
+            token_mint: Pubkey::new_unique(),
+            amount: 700,
+            requested_at: 1_700_000_000,
+            bump: 0,
+        };
+        let message = pending.release_message(&position, 1_700_000_100);
+        assert_eq!(
+            message,
+            YieldMessage::WithdrawalRelease {
+                user: owner.address,
+                amount: 700,
+                token_mint: pending.token_mint,
+                protocol_id: 2,
+                destination_address: vec![0xcd; 20],
+                timestamp: 1_700_000_100,
+            }
+        );
+        assert!(message.validate().is_ok());
+    }
+}
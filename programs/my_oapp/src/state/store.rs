@@ -15,13 +15,25 @@ impl Store {
 }
 
 // The LzReceiveTypesAccounts PDA is used by the Executor as a prerequisite to calling `lz_receive`.
+// It lists, in order, the accounts the Executor passes to `lz_receive_types`.
 #[account]
 pub struct LzReceiveTypesAccounts {
     pub store: Pubkey, // This is required and should be consistent.
+    pub endpoint: Pubkey,
+    pub registry: Pubkey, // Protocol routes used to derive the accounts of vault and protocol messages
 }
 
 impl LzReceiveTypesAccounts {
     pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
 }
 
+// The LzComposeTypesAccounts PDA plays the same role for `lz_compose_types`.
+#[account]
+pub struct LzComposeTypesAccounts {
+    pub store: Pubkey,
+    pub registry: Pubkey,
+}
 
+impl LzComposeTypesAccounts {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+}
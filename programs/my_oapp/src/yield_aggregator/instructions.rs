@@ -470,6 +470,6 @@ impl EmergencyPause<'_> {
     }
 }
 
-// Note: GetOptimalStrategy is removed to reduce stack usage.
-// This functionality should be implemented client-side by fetching protocol data 
-// and calculating the optimal strategy based on user preferences.
\ No newline at end of file
+// Note: GetOptimalStrategy now lives in instructions/get_optimal_strategy.rs as a read-only
+// instruction. It reads ProtocolInfo accounts from remaining_accounts instead of declaring them,
+// which keeps the stack small.
\ No newline at end of file
@@ -2,3 +2,8 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+/// Fixed-point scale of `ProtocolInfo::yield_index` (1e18 == 1.0)
+pub const INDEX_SCALE: u128 = 1_000_000_000_000_000_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
@@ -22,4 +22,23 @@ pub enum YieldAggregatorError {
     ProtocolCapacityExceeded,
     #[msg("Invalid chain ID")]
     InvalidChainId,
+    #[msg("Insufficient deposited balance")]
+    InsufficientBalance,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Too many open positions")]
+    TooManyPositions,
+    #[msg("Vaults hold different tokens")]
+    VaultMintMismatch,
+    #[msg("Vault does not hold enough tokens")]
+    InsufficientLiquidity,
+    #[msg("Fee exceeds the maximum allowed")]
+    FeeTooHigh,
+    #[msg("No fees to claim")]
+    NoFeesToClaim,
+    #[msg("Invalid split deposit legs")]
+    InvalidSplit,
+    #[msg("No yield to harvest")]
+    NoYieldToHarvest,
 }
+
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::fees::FeeTotals;
+
+#[event]
+pub struct FeesClaimed {
+    pub protocol: Pubkey,
+    pub recipient: Pubkey,
+    pub shares: u64,
+    pub assets: u64,
+    pub lifetime_fees: FeeTotals,
+    pub total_fees_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SplitDepositLeg {
+    pub user: Pubkey,
+    pub protocol: Pubkey,
+    pub leg: u8,
+    pub assets: u64,
+    pub shares: u64,
+    pub fee: u64,
+    pub apy: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldHarvested {
+    pub protocol: Pubkey,
+    pub amount: u64,
+    pub unharvested_yield: u64,
+    pub timestamp: i64,
+}
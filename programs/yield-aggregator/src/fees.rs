@@ -0,0 +1,310 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, INDEX_SCALE, SECONDS_PER_YEAR};
+use crate::error::YieldAggregatorError;
+use crate::math::{mul_div, Rounding};
+use crate::state::YieldVault;
+
+// Hard caps the admin cannot exceed, in basis points
+pub const MAX_PERFORMANCE_FEE_BPS: u64 = 3_000; // 30% of yield above the high-water mark
+pub const MAX_MANAGEMENT_FEE_BPS: u64 = 500; // 5% of assets per year
+pub const MAX_DEPOSIT_FEE_BPS: u64 = 100;
+pub const MAX_WITHDRAWAL_FEE_BPS: u64 = 100;
+pub const MAX_REBALANCE_FEE_BPS: u64 = 100;
+
+/// Same variant order as `msg_codec::FeeType` on the OApp side so the Borsh encoding matches.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FeeType {
+    Performance,
+    Management,
+    Withdrawal,
+    Rebalance,
+    Deposit,
+}
+
+impl FeeType {
+    pub fn max_bps(&self) -> u64 {
+        match self {
+            FeeType::Performance => MAX_PERFORMANCE_FEE_BPS,
+            FeeType::Management => MAX_MANAGEMENT_FEE_BPS,
+            FeeType::Withdrawal => MAX_WITHDRAWAL_FEE_BPS,
+            FeeType::Rebalance => MAX_REBALANCE_FEE_BPS,
+            FeeType::Deposit => MAX_DEPOSIT_FEE_BPS,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, InitSpace)]
+pub struct FeeConfig {
+    pub performance_bps: u64,
+    pub management_bps: u64,
+    pub deposit_bps: u64,
+    pub withdrawal_bps: u64,
+    pub rebalance_bps: u64,
+}
+
+impl FeeConfig {
+    pub fn set(&mut self, fee_type: FeeType, bps: u64) -> Result<()> {
+        require!(bps <= fee_type.max_bps(), YieldAggregatorError::FeeTooHigh);
+        match fee_type {
+            FeeType::Performance => self.performance_bps = bps,
+            FeeType::Management => self.management_bps = bps,
+            FeeType::Withdrawal => self.withdrawal_bps = bps,
+            FeeType::Rebalance => self.rebalance_bps = bps,
+            FeeType::Deposit => self.deposit_bps = bps,
+        }
+        Ok(())
+    }
+}
+
+/// Fee amounts in asset terms, split by type.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, InitSpace)]
+pub struct FeeTotals {
+    pub performance: u64,
+    pub management: u64,
+    pub deposit: u64,
+    pub withdrawal: u64,
+    pub rebalance: u64,
+}
+
+impl FeeTotals {
+    pub fn record(&mut self, fee_type: FeeType, assets: u64) -> Result<()> {
+        let total = match fee_type {
+            FeeType::Performance => &mut self.performance,
+            FeeType::Management => &mut self.management,
+            FeeType::Withdrawal => &mut self.withdrawal,
+            FeeType::Rebalance => &mut self.rebalance,
+            FeeType::Deposit => &mut self.deposit,
+        };
+        *total = total.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn add(&mut self, other: &FeeTotals) -> Result<()> {
+        self.record(FeeType::Performance, other.performance)?;
+        self.record(FeeType::Management, other.management)?;
+        self.record(FeeType::Deposit, other.deposit)?;
+        self.record(FeeType::Withdrawal, other.withdrawal)?;
+        self.record(FeeType::Rebalance, other.rebalance)
+    }
+
+    pub fn total(&self) -> Result<u64> {
+        [self.management, self.deposit, self.withdrawal, self.rebalance]
+            .into_iter()
+            .try_fold(self.performance, u64::checked_add)
+            .ok_or(YieldAggregatorError::MathOverflow.into())
+    }
+}
+
+/// Fee charged on top of `assets` (the user receives or invests exactly `assets`).
+pub fn fee_on_raw(assets: u64, bps: u64) -> Result<u64> {
+    mul_div(assets as u128, bps as u128, BPS_DENOMINATOR as u128, Rounding::Up)
+}
+
+/// Fee already included in `assets` (the user pays or receives `assets` in total).
+pub fn fee_on_total(assets: u64, bps: u64) -> Result<u64> {
+    mul_div(
+        assets as u128,
+        bps as u128,
+        BPS_DENOMINATOR as u128 + bps as u128,
+        Rounding::Up,
+    )
+}
+
+/// Result of pricing a vault entry or exit. `assets` moves between the user and the vault,
+/// `shares` are minted to or burned from the user and `fee_shares`, worth `fee` assets, go to
+/// the fee recipient.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VaultQuote {
+    pub assets: u64,
+    pub shares: u64,
+    pub fee: u64,
+    pub fee_shares: u64,
+}
+
+// Fee-aware ERC-4626 quotes, following OpenZeppelin's ERC4626Fees rounding. Entry and exit
+// fees stay in the vault and are credited to the fee recipient as shares.
+impl YieldVault {
+    pub fn quote_deposit(&self, assets: u64, bps: u64) -> Result<VaultQuote> {
+        let fee = fee_on_total(assets, bps)?;
+        Ok(VaultQuote {
+            assets,
+            shares: self.convert_to_shares(assets - fee, Rounding::Down)?,
+            fee,
+            fee_shares: self.convert_to_shares(fee, Rounding::Down)?,
+        })
+    }
+
+    pub fn quote_mint(&self, shares: u64, bps: u64) -> Result<VaultQuote> {
+        let net = self.convert_to_assets(shares, Rounding::Up)?;
+        let fee = fee_on_raw(net, bps)?;
+        Ok(VaultQuote {
+            assets: net.checked_add(fee).ok_or(YieldAggregatorError::MathOverflow)?,
+            shares,
+            fee,
+            fee_shares: self.convert_to_shares(fee, Rounding::Down)?,
+        })
+    }
+
+    pub fn quote_withdraw(&self, assets: u64, bps: u64) -> Result<VaultQuote> {
+        let fee = fee_on_raw(assets, bps)?;
+        Ok(VaultQuote {
+            assets,
+            shares: self.convert_to_shares(
+                assets.checked_add(fee).ok_or(YieldAggregatorError::MathOverflow)?,
+                Rounding::Up,
+            )?,
+            fee,
+            fee_shares: self.convert_to_shares(fee, Rounding::Down)?,
+        })
+    }
+
+    pub fn quote_redeem(&self, shares: u64, bps: u64) -> Result<VaultQuote> {
+        let gross = self.convert_to_assets(shares, Rounding::Down)?;
+        let fee = fee_on_total(gross, bps)?;
+        Ok(VaultQuote {
+            assets: gross - fee,
+            shares,
+            fee,
+            fee_shares: self.convert_to_shares(fee, Rounding::Down)?,
+        })
+    }
+
+    /// Price of one share in INDEX_SCALE fixed point, using the same virtual offsets as the
+    /// share conversions.
+    pub fn share_price(&self) -> u128 {
+        (self.total_assets as u128 + 1) * INDEX_SCALE / (self.total_shares as u128 + 1)
+    }
+
+    /// Accrues the time-based management fee and the performance fee on any share price gain
+    /// above the high-water mark. Returns the value of the fees taken.
+    pub fn accrue_fees(&mut self, fees: &FeeConfig, now: i64) -> Result<FeeTotals> {
+        let mut accrued = FeeTotals::default();
+
+        let elapsed = now.saturating_sub(self.last_fee_accrual).max(0) as u128;
+        if elapsed > 0 && fees.management_bps > 0 {
+            let fee_assets = mul_div(
+                self.total_assets as u128,
+                fees.management_bps as u128 * elapsed,
+                BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128,
+                Rounding::Down,
+            )?;
+            if self.mint_fee_shares(fee_assets)? > 0 {
+                accrued.record(FeeType::Management, fee_assets)?;
+            }
+        }
+        self.last_fee_accrual = self.last_fee_accrual.max(now);
+
+        let price = self.share_price();
+        if price > self.high_water_mark {
+            if fees.performance_bps > 0 {
+                let gain = mul_div(
+                    price - self.high_water_mark,
+                    self.total_shares as u128 + 1,
+                    INDEX_SCALE,
+                    Rounding::Down,
+                )?;
+                let fee_assets = mul_div(
+                    gain as u128,
+                    fees.performance_bps as u128,
+                    BPS_DENOMINATOR as u128,
+                    Rounding::Down,
+                )?;
+                if self.mint_fee_shares(fee_assets)? > 0 {
+                    accrued.record(FeeType::Performance, fee_assets)?;
+                }
+            }
+            self.high_water_mark = self.share_price();
+        }
+
+        Ok(accrued)
+    }
+
+    /// Dilutes existing holders by minting shares worth `fee_assets` to the fee recipient.
+    fn mint_fee_shares(&mut self, fee_assets: u64) -> Result<u64> {
+        if fee_assets == 0 || fee_assets >= self.total_assets {
+            return Ok(0);
+        }
+        let shares = mul_div(
+            fee_assets as u128,
+            self.total_shares as u128 + 1,
+            (self.total_assets - fee_assets) as u128 + 1,
+            Rounding::Down,
+        )?;
+        self.total_shares =
+            self.total_shares.checked_add(shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        self.fee_shares =
+            self.fee_shares.checked_add(shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        Ok(shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_caps() {
+        let mut fees = FeeConfig::default();
+        assert!(fees.set(FeeType::Performance, MAX_PERFORMANCE_FEE_BPS).is_ok());
+        assert!(fees.set(FeeType::Management, MAX_MANAGEMENT_FEE_BPS + 1).is_err());
+        assert_eq!(fees.performance_bps, MAX_PERFORMANCE_FEE_BPS);
+        assert_eq!(fees.management_bps, 0);
+    }
+
+    #[test]
+    fn test_deposit_fee_is_taken_from_assets() {
+        let vault = YieldVault::for_test(0, 0);
+        // 1% fee included in 10_100 assets
+        let quote = vault.quote_deposit(10_100, 100).unwrap();
+        assert_eq!(quote.shares, 10_000);
+        assert_eq!(quote.fee, 100);
+        assert_eq!(quote.fee_shares, 100);
+
+        let quote = vault.quote_mint(10_000, 100).unwrap();
+        assert_eq!(quote.assets, 10_100);
+    }
+
+    #[test]
+    fn test_withdraw_fee_is_charged_on_top() {
+        let vault = YieldVault::for_test(10_000, 10_000);
+        let quote = vault.quote_withdraw(1_000, 100).unwrap();
+        assert_eq!(quote.shares, 1_010);
+        assert_eq!(quote.fee_shares, 10);
+
+        let quote = vault.quote_redeem(1_010, 100).unwrap();
+        assert_eq!(quote.assets, 1_000);
+    }
+
+    #[test]
+    fn test_management_fee_accrues_pro_rata() {
+        let mut vault = YieldVault::for_test(1_000_000, 1_000_000);
+        let fees = FeeConfig { management_bps: 200, ..Default::default() };
+
+        // Half a year at 2% per year is 1% of assets
+        let accrued = vault.accrue_fees(&fees, (SECONDS_PER_YEAR / 2) as i64).unwrap();
+        assert_eq!(accrued.management, 10_000);
+        let fee_value = vault.convert_to_assets(vault.fee_shares, Rounding::Down).unwrap();
+        assert!((9_990..=10_000).contains(&fee_value));
+    }
+
+    #[test]
+    fn test_performance_fee_only_above_high_water_mark() {
+        let mut vault = YieldVault::for_test(1_000_000, 1_000_000);
+        let fees = FeeConfig { performance_bps: 1_000, ..Default::default() };
+
+        // 10% gain, 10% of it is charged
+        vault.total_assets = 1_100_000;
+        let accrued = vault.accrue_fees(&fees, 0).unwrap();
+        assert_eq!(accrued.performance, 9_999);
+        let fee_value = vault.convert_to_assets(vault.fee_shares, Rounding::Down).unwrap();
+        assert!((9_990..=10_000).contains(&fee_value));
+
+        // Share price drops and recovers to the mark: nothing more is charged
+        let mark = vault.high_water_mark;
+        vault.total_assets = 1_000_000;
+        assert_eq!(vault.accrue_fees(&fees, 0).unwrap().total().unwrap(), 0);
+        assert_eq!(vault.high_water_mark, mark);
+        vault.total_assets = 1_100_000;
+        assert_eq!(vault.accrue_fees(&fees, 0).unwrap().total().unwrap(), 0);
+    }
+}
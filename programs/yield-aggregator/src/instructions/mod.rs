@@ -1,6 +1,27 @@
 use anchor_lang::prelude::*;
-use crate::{state::*, error::YieldAggregatorError};
-use crate::{YIELD_AGGREGATOR_SEED, PROTOCOL_SEED, USER_POSITION_SEED};
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::{state::*, constants::INDEX_SCALE, error::YieldAggregatorError, events::*};
+use crate::math::Rounding;
+use crate::fees::{FeeConfig, FeeTotals, FeeType, VaultQuote};
+use crate::strategy::{rank, split_by_weights, MAX_STRATEGY_PROTOCOLS};
+use crate::{YIELD_AGGREGATOR_SEED, PROTOCOL_SEED, USER_POSITION_SEED, USER_SUMMARY_SEED, YIELD_VAULT_SEED, RECEIPT_MINT_SEED};
+
+/// Brings the protocol index and its vault up to the current time before any share math runs.
+/// Index yield is only recorded as owed to the vault here; it reaches total_assets, TVL and the
+/// fee base when `HarvestYield` funds it with tokens.
+fn accrue_protocol(
+    protocol: &mut ProtocolInfo,
+    vault: &mut YieldVault,
+    aggregator: &mut YieldAggregator,
+) -> Result<u128> {
+    let now = Clock::get()?.unix_timestamp;
+    let index = protocol.accrue(now)?;
+    vault.sync(index)?;
+    let fees = vault.accrue_fees(&aggregator.fee_config, now)?;
+    aggregator.fees_collected.add(&fees)?;
+    Ok(index)
+}
 
 // ============================== Initialize Yield Aggregator ==============================
 
@@ -27,15 +48,18 @@ pub struct InitializeYieldAggregator<'info> {
 impl InitializeYieldAggregator<'_> {
     pub fn apply(ctx: &mut Context<Self>, params: &InitializeYieldAggregatorParams) -> Result<()> {
         let aggregator = &mut ctx.accounts.yield_aggregator;
-        let timestamp = Clock::get()?.unix_timestamp;
-        
+
         aggregator.admin = params.admin;
         aggregator.total_protocols = 0;
         aggregator.total_tvl = 0;
         aggregator.total_yield_earned = 0;
         aggregator.emergency_paused = false;
-        aggregator.fee_rate = 0;
+        aggregator.fee_config = FeeConfig::default();
         aggregator.fee_recipient = params.admin;
+        aggregator.fees_collected = FeeTotals::default();
+        aggregator.total_fees_claimed = 0;
+        aggregator.remote_authority = Pubkey::default();
+        aggregator.remote_admin = Pubkey::default();
         aggregator.bump = ctx.bumps.yield_aggregator;
 
         Ok(())
@@ -92,9 +116,86 @@ impl AddProtocol<'_> {
         protocol.risk_score = params.risk_score;
         protocol.is_active = true;
         protocol.last_update = timestamp;
+        protocol.yield_index = INDEX_SCALE;
+        protocol.last_accrual = timestamp;
         protocol.bump = ctx.bumps.protocol_info;
 
-        ctx.accounts.yield_aggregator.total_protocols += 1;
+        let aggregator = &mut ctx.accounts.yield_aggregator;
+        aggregator.total_protocols =
+            aggregator.total_protocols.checked_add(1).ok_or(YieldAggregatorError::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+// ============================== Initialize Vault ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeVaultParams {
+    pub protocol_name: String,
+}
+
+#[derive(Accounts)]
+#[instruction(params: InitializeVaultParams)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + YieldVault::INIT_SPACE,
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump
+    )]
+    pub yield_vault: Account<'info, YieldVault>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = yield_vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [RECEIPT_MINT_SEED, yield_vault.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = yield_vault
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
+        bump = protocol_info.bump
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    #[account(
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        has_one = admin @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_aggregator: Account<'info, YieldAggregator>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeVault<'_> {
+    pub fn apply(ctx: &mut Context<Self>, _params: &InitializeVaultParams) -> Result<()> {
+        let vault = &mut ctx.accounts.yield_vault;
+        vault.protocol = ctx.accounts.protocol_info.key();
+        vault.mint = ctx.accounts.mint.key();
+        vault.authority = ctx.accounts.admin.key();
+        vault.receipt_mint = ctx.accounts.receipt_mint.key();
+        vault.total_shares = 0;
+        vault.total_assets = 0;
+        vault.last_index = ctx.accounts.protocol_info.yield_index;
+        vault.unharvested_yield = 0;
+        vault.fee_shares = 0;
+        vault.high_water_mark = INDEX_SCALE;
+        vault.last_fee_accrual = Clock::get()?.unix_timestamp;
+        vault.bump = ctx.bumps.yield_vault;
 
         Ok(())
     }
@@ -117,89 +218,744 @@ pub struct DepositForYield<'info> {
         init_if_needed,
         payer = user,
         space = 8 + UserPosition::INIT_SPACE,
-        seeds = [USER_POSITION_SEED, user.key().as_ref()],
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), protocol_info.key().as_ref()],
         bump
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub user_position: Box<Account<'info, UserPosition>>,
     #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserSummary::INIT_SPACE,
+        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_summary: Box<Account<'info, UserSummary>>,
+    #[account(
+        mut,
         seeds = [PROTOCOL_SEED, params.target_protocol.as_bytes()],
         bump = protocol_info.bump,
         constraint = protocol_info.is_active @ YieldAggregatorError::ProtocolInactive,
         constraint = protocol_info.current_apy >= params.min_apy @ YieldAggregatorError::ApyTooLow
     )]
-    pub protocol_info: Account<'info, ProtocolInfo>,
+    pub protocol_info: Box<Account<'info, ProtocolInfo>>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump = yield_vault.bump,
+        has_one = mint,
+        has_one = receipt_mint
+    )]
+    pub yield_vault: Box<Account<'info, YieldVault>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = yield_vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub user_receipt_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub receipt_mint: Box<Account<'info, Mint>>,
     #[account(
         mut,
         seeds = [YIELD_AGGREGATOR_SEED],
         bump = yield_aggregator.bump,
         constraint = !yield_aggregator.emergency_paused @ YieldAggregatorError::EmergencyPaused
     )]
-    pub yield_aggregator: Account<'info, YieldAggregator>,
+    pub yield_aggregator: Box<Account<'info, YieldAggregator>>,
     #[account(mut)]
     pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl DepositForYield<'_> {
+    /// ERC-4626 `deposit`: `params.amount` is the exact amount of assets to deposit.
     pub fn apply(ctx: &mut Context<Self>, params: &DepositForYieldParams) -> Result<()> {
         require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
 
+        accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+        let bps = ctx.accounts.yield_aggregator.fee_config.deposit_bps;
+        let quote = ctx.accounts.yield_vault.quote_deposit(params.amount, bps)?;
+        Self::settle(ctx, quote)
+    }
+
+    /// ERC-4626 `mint`: `params.amount` is the exact number of shares to mint.
+    pub fn apply_mint(ctx: &mut Context<Self>, params: &DepositForYieldParams) -> Result<()> {
+        require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
+
+        accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+        let bps = ctx.accounts.yield_aggregator.fee_config.deposit_bps;
+        let quote = ctx.accounts.yield_vault.quote_mint(params.amount, bps)?;
+        Self::settle(ctx, quote)
+    }
+
+    fn settle(ctx: &mut Context<Self>, quote: VaultQuote) -> Result<()> {
+        let VaultQuote { assets, shares, fee, fee_shares } = quote;
+        require!(assets > 0 && shares > 0, YieldAggregatorError::InvalidAmount);
+        ctx.accounts.protocol_info.check_capacity(assets)?;
+
+        // Move the tokens into vault custody before crediting anything
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            assets,
+        )?;
+
+        // Shares are issued as receipt tokens so positions can move between wallets
+        let protocol_key = ctx.accounts.protocol_info.key();
+        let seeds: &[&[u8]] =
+            &[YIELD_VAULT_SEED, protocol_key.as_ref(), &[ctx.accounts.yield_vault.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.user_receipt_account.to_account_info(),
+                    authority: ctx.accounts.yield_vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            shares,
+        )?;
+
+        // The deposit fee stays in the vault and is credited to the fee recipient as shares
+        let vault = &mut ctx.accounts.yield_vault;
+        vault.total_assets =
+            vault.total_assets.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        let minted = shares.checked_add(fee_shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        vault.total_shares =
+            vault.total_shares.checked_add(minted).ok_or(YieldAggregatorError::MathOverflow)?;
+        vault.fee_shares =
+            vault.fee_shares.checked_add(fee_shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        ctx.accounts.yield_aggregator.fees_collected.record(FeeType::Deposit, fee)?;
+
         let timestamp = Clock::get()?.unix_timestamp;
         let user_key = ctx.accounts.user.key();
-        
+
         let user_position = &mut ctx.accounts.user_position;
         user_position.user = user_key;
-        user_position.total_deposits += params.amount;
-        user_position.position_count += 1;
+        user_position.protocol = protocol_key;
+        user_position.accrue(ctx.accounts.protocol_info.yield_index)?;
+        user_position.credit(assets, shares, ctx.accounts.protocol_info.current_apy)?;
         user_position.last_activity = timestamp;
         user_position.bump = ctx.bumps.user_position;
 
-        ctx.accounts.yield_aggregator.total_tvl += params.amount;
+        let position_key = ctx.accounts.user_position.key();
+        let user_summary = &mut ctx.accounts.user_summary;
+        user_summary.user = user_key;
+        user_summary.track(position_key)?;
+        user_summary.last_activity = timestamp;
+        user_summary.bump = ctx.bumps.user_summary;
+
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        protocol_info.tvl =
+            protocol_info.tvl.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        let aggregator = &mut ctx.accounts.yield_aggregator;
+        aggregator.total_tvl =
+            aggregator.total_tvl.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
 
         Ok(())
     }
 }
 
+// ============================== Deposit Split ==============================
+
+/// Accounts each leg of a split deposit passes through remaining_accounts, in this order:
+/// protocol_info, yield_vault, vault_token_account, receipt_mint, user_receipt_account and
+/// user_position. All six must be writable, and the receipt token account must already exist.
+pub const SPLIT_LEG_ACCOUNTS: usize = 6;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum SplitMode {
+    /// One weight per leg in basis points, summing to 10_000
+    Weighted { weights_bps: Vec<u16> },
+    /// Rank the legs with the strategy scorer and fill them in order, skipping riskier protocols
+    Auto { max_risk_score: u8 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositSplitParams {
+    pub amount: u64,
+    pub mode: SplitMode,
+    pub min_apy: u64,
+}
+
+#[derive(Accounts)]
+pub struct DepositSplit<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserSummary::INIT_SPACE,
+        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_summary: Box<Account<'info, UserSummary>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        constraint = !yield_aggregator.emergency_paused @ YieldAggregatorError::EmergencyPaused
+    )]
+    pub yield_aggregator: Box<Account<'info, YieldAggregator>>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// One leg of a split deposit, loaded and checked from remaining_accounts.
+struct SplitLeg<'info> {
+    protocol_info: Account<'info, ProtocolInfo>,
+    yield_vault: Account<'info, YieldVault>,
+    vault_token_account: Account<'info, TokenAccount>,
+    receipt_mint: AccountInfo<'info>,
+    user_receipt_account: Account<'info, TokenAccount>,
+    user_position: Account<'info, UserPosition>,
+}
+
+impl<'info> DepositSplit<'info> {
+    /// Spreads `params.amount` over several protocols in one transaction. Each leg is an ordinary
+    /// deposit into that protocol's vault and position.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, DepositSplit<'info>>,
+        params: &DepositSplitParams,
+    ) -> Result<()> {
+        require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty()
+                && remaining.len() % SPLIT_LEG_ACCOUNTS == 0
+                && remaining.len() / SPLIT_LEG_ACCOUNTS <= MAX_STRATEGY_PROTOCOLS,
+            YieldAggregatorError::InvalidSplit
+        );
+
+        let mut legs: Vec<SplitLeg<'info>> =
+            Vec::with_capacity(remaining.len() / SPLIT_LEG_ACCOUNTS);
+        for accounts in remaining.chunks(SPLIT_LEG_ACCOUNTS) {
+            let leg = Self::load_leg(
+                accounts,
+                &ctx.accounts.user,
+                ctx.accounts.mint.key(),
+                &ctx.accounts.system_program,
+            )?;
+            require!(
+                legs.iter().all(|l| l.protocol_info.key() != leg.protocol_info.key()),
+                YieldAggregatorError::InvalidSplit
+            );
+            legs.push(leg);
+        }
+
+        // Accrue first so capacity and share prices are current when the amounts are decided
+        for leg in legs.iter_mut() {
+            accrue_protocol(
+                &mut leg.protocol_info,
+                &mut leg.yield_vault,
+                &mut ctx.accounts.yield_aggregator,
+            )?;
+        }
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let amounts = match &params.mode {
+            SplitMode::Weighted { weights_bps } => {
+                require!(weights_bps.len() == legs.len(), YieldAggregatorError::InvalidSplit);
+                split_by_weights(params.amount, weights_bps)
+                    .ok_or(YieldAggregatorError::InvalidSplit)?
+            }
+            SplitMode::Auto { max_risk_score } => {
+                let candidates: Vec<(Pubkey, &ProtocolInfo)> =
+                    legs.iter().map(|l| (l.protocol_info.key(), &*l.protocol_info)).collect();
+                let strategy =
+                    rank(&candidates, params.amount, *max_risk_score, params.min_apy, timestamp);
+                require!(strategy.unallocated == 0, YieldAggregatorError::ProtocolCapacityExceeded);
+                legs.iter()
+                    .map(|l| {
+                        strategy
+                            .allocations
+                            .iter()
+                            .find(|a| a.protocol == l.protocol_info.key())
+                            .map_or(0, |a| a.amount)
+                    })
+                    .collect()
+            }
+        };
+
+        let user_key = ctx.accounts.user.key();
+        let bps = ctx.accounts.yield_aggregator.fee_config.deposit_bps;
+        for (index, (leg, assets)) in legs.iter_mut().zip(amounts).enumerate() {
+            if assets == 0 {
+                continue;
+            }
+            require!(leg.protocol_info.is_active, YieldAggregatorError::ProtocolInactive);
+            require!(
+                leg.protocol_info.current_apy >= params.min_apy,
+                YieldAggregatorError::ApyTooLow
+            );
+            let quote = leg.yield_vault.quote_deposit(assets, bps)?;
+            require!(quote.shares > 0, YieldAggregatorError::InvalidAmount);
+            leg.protocol_info.check_capacity(assets)?;
+
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.user_token_account.to_account_info(),
+                        to: leg.vault_token_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                assets,
+            )?;
+
+            let protocol_key = leg.protocol_info.key();
+            let seeds: &[&[u8]] =
+                &[YIELD_VAULT_SEED, protocol_key.as_ref(), &[leg.yield_vault.bump]];
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: leg.receipt_mint.clone(),
+                        to: leg.user_receipt_account.to_account_info(),
+                        authority: leg.yield_vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                quote.shares,
+            )?;
+
+            let vault = &mut leg.yield_vault;
+            vault.total_assets =
+                vault.total_assets.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+            let minted = quote
+                .shares
+                .checked_add(quote.fee_shares)
+                .ok_or(YieldAggregatorError::MathOverflow)?;
+            vault.total_shares =
+                vault.total_shares.checked_add(minted).ok_or(YieldAggregatorError::MathOverflow)?;
+            vault.fee_shares = vault
+                .fee_shares
+                .checked_add(quote.fee_shares)
+                .ok_or(YieldAggregatorError::MathOverflow)?;
+
+            let yield_index = leg.protocol_info.yield_index;
+            let apy = leg.protocol_info.current_apy;
+            let user_position = &mut leg.user_position;
+            user_position.accrue(yield_index)?;
+            user_position.credit(assets, quote.shares, apy)?;
+            user_position.last_activity = timestamp;
+            ctx.accounts.user_summary.track(user_position.key())?;
+
+            let protocol_info = &mut leg.protocol_info;
+            protocol_info.tvl =
+                protocol_info.tvl.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+            let aggregator = &mut ctx.accounts.yield_aggregator;
+            aggregator.total_tvl =
+                aggregator.total_tvl.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+            aggregator.fees_collected.record(FeeType::Deposit, quote.fee)?;
+
+            emit!(SplitDepositLeg {
+                user: user_key,
+                protocol: protocol_key,
+                leg: index as u8,
+                assets,
+                shares: quote.shares,
+                fee: quote.fee,
+                apy,
+                timestamp,
+            });
+        }
+
+        // Remaining accounts are not written back automatically
+        for leg in legs.iter() {
+            leg.protocol_info.exit(&crate::ID)?;
+            leg.yield_vault.exit(&crate::ID)?;
+            leg.user_position.exit(&crate::ID)?;
+        }
+
+        let user_summary = &mut ctx.accounts.user_summary;
+        user_summary.user = user_key;
+        user_summary.last_activity = timestamp;
+        user_summary.bump = ctx.bumps.user_summary;
+
+        Ok(())
+    }
+
+    fn load_leg(
+        accounts: &'info [AccountInfo<'info>],
+        user: &Signer<'info>,
+        mint: Pubkey,
+        system_program: &Program<'info, System>,
+    ) -> Result<SplitLeg<'info>> {
+        let protocol_info = Account::<ProtocolInfo>::try_from(&accounts[0])?;
+        let yield_vault = Account::<YieldVault>::try_from(&accounts[1])?;
+        let vault_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        let receipt_mint = accounts[3].clone();
+        let user_receipt_account = Account::<TokenAccount>::try_from(&accounts[4])?;
+
+        require_keys_eq!(yield_vault.protocol, protocol_info.key(), YieldAggregatorError::InvalidSplit);
+        require_keys_eq!(yield_vault.mint, mint, YieldAggregatorError::VaultMintMismatch);
+        require_keys_eq!(
+            vault_token_account.key(),
+            get_associated_token_address(&yield_vault.key(), &mint),
+            YieldAggregatorError::InvalidSplit
+        );
+        require_keys_eq!(yield_vault.receipt_mint, receipt_mint.key(), YieldAggregatorError::InvalidSplit);
+        require_keys_eq!(user_receipt_account.mint, receipt_mint.key(), YieldAggregatorError::InvalidSplit);
+        require_keys_eq!(user_receipt_account.owner, user.key(), YieldAggregatorError::InvalidSplit);
+
+        let user_position =
+            Self::load_or_create_position(&accounts[5], user, protocol_info.key(), system_program)?;
+
+        Ok(SplitLeg {
+            protocol_info,
+            yield_vault,
+            vault_token_account,
+            receipt_mint,
+            user_receipt_account,
+            user_position,
+        })
+    }
+
+    /// Remaining accounts can't use `init_if_needed`, so a first-time position is created here
+    /// with the same seeds and space as in DepositForYield.
+    fn load_or_create_position(
+        info: &'info AccountInfo<'info>,
+        user: &Signer<'info>,
+        protocol: Pubkey,
+        system_program: &Program<'info, System>,
+    ) -> Result<Account<'info, UserPosition>> {
+        let user_key = user.key();
+        let (expected, bump) = Pubkey::find_program_address(
+            &[USER_POSITION_SEED, user_key.as_ref(), protocol.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(info.key(), expected, YieldAggregatorError::InvalidSplit);
+
+        if info.data_is_empty() {
+            let space = 8 + UserPosition::INIT_SPACE;
+            let seeds: &[&[u8]] = &[USER_POSITION_SEED, user_key.as_ref(), protocol.as_ref(), &[bump]];
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: user.to_account_info(),
+                        to: info.clone(),
+                    },
+                    &[seeds],
+                ),
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                &crate::ID,
+            )?;
+
+            let position = UserPosition {
+                user: user_key,
+                protocol,
+                principal: 0,
+                shares: 0,
+                entry_apy: 0,
+                accrued_yield: 0,
+                index_snapshot: 0,
+                last_activity: 0,
+                bump,
+            };
+            let mut data = info.try_borrow_mut_data()?;
+            position.try_serialize(&mut &mut data[..])?;
+        }
+
+        Account::try_from(info)
+    }
+}
+
 // ============================== Withdraw Yield ==============================
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct WithdrawYieldParams {
     pub amount: u64,
+    pub protocol_name: String,
     pub target_chain_id: u32,
 }
 
 #[derive(Accounts)]
+#[instruction(params: WithdrawYieldParams)]
 pub struct WithdrawYield<'info> {
+    // Receipt tokens are transferable, so a holder may redeem without ever having deposited.
+    // An existing position must belong to the protocol being withdrawn from.
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPosition::INIT_SPACE,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), protocol_info.key().as_ref()],
+        bump,
+        constraint = user_position.protocol == Pubkey::default()
+            || user_position.protocol == protocol_info.key() @ YieldAggregatorError::Unauthorized
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserSummary::INIT_SPACE,
+        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_summary: Box<Account<'info, UserSummary>>,
     #[account(
         mut,
-        seeds = [USER_POSITION_SEED, user.key().as_ref()],
-        bump = user_position.bump,
-        has_one = user @ YieldAggregatorError::Unauthorized
+        seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
+        bump = protocol_info.bump
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub protocol_info: Box<Account<'info, ProtocolInfo>>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump = yield_vault.bump,
+        has_one = mint,
+        has_one = receipt_mint,
+        constraint = yield_vault.protocol == protocol_info.key() @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_vault: Box<Account<'info, YieldVault>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = yield_vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = user
+    )]
+    pub user_receipt_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub receipt_mint: Box<Account<'info, Mint>>,
     #[account(
         mut,
         seeds = [YIELD_AGGREGATOR_SEED],
         bump = yield_aggregator.bump,
         constraint = !yield_aggregator.emergency_paused @ YieldAggregatorError::EmergencyPaused
     )]
-    pub yield_aggregator: Account<'info, YieldAggregator>,
+    pub yield_aggregator: Box<Account<'info, YieldAggregator>>,
     #[account(mut)]
     pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 impl WithdrawYield<'_> {
+    /// ERC-4626 `withdraw`: `params.amount` is the exact amount of assets to receive.
     pub fn apply(ctx: &mut Context<Self>, params: &WithdrawYieldParams) -> Result<()> {
         require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
 
+        accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+        let bps = ctx.accounts.yield_aggregator.fee_config.withdrawal_bps;
+        let quote = ctx.accounts.yield_vault.quote_withdraw(params.amount, bps)?;
+        Self::settle(ctx, quote)
+    }
+
+    /// ERC-4626 `redeem`: `params.amount` is the exact number of shares to burn.
+    pub fn apply_redeem(ctx: &mut Context<Self>, params: &WithdrawYieldParams) -> Result<()> {
+        require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
+
+        accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+        let bps = ctx.accounts.yield_aggregator.fee_config.withdrawal_bps;
+        let quote = ctx.accounts.yield_vault.quote_redeem(params.amount, bps)?;
+        Self::settle(ctx, quote)
+    }
+
+    fn settle(ctx: &mut Context<Self>, quote: VaultQuote) -> Result<()> {
+        let VaultQuote { assets, shares, fee, fee_shares } = quote;
+        require!(assets > 0, YieldAggregatorError::InvalidAmount);
+        require!(
+            shares <= ctx.accounts.user_receipt_account.amount
+                && assets <= ctx.accounts.yield_vault.total_assets,
+            YieldAggregatorError::InsufficientBalance
+        );
+        // Accrued yield only becomes withdrawable once it has been harvested into the vault
+        require!(
+            assets <= ctx.accounts.vault_token_account.amount,
+            YieldAggregatorError::InsufficientLiquidity
+        );
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.user_receipt_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        // The vault PDA owns the token account, so it signs the transfer back to the user
+        let protocol_key = ctx.accounts.protocol_info.key();
+        let seeds: &[&[u8]] =
+            &[YIELD_VAULT_SEED, protocol_key.as_ref(), &[ctx.accounts.yield_vault.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.yield_vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            assets,
+        )?;
+
+        // Part of the burned shares is re-issued to the fee recipient as the withdrawal fee
+        let vault = &mut ctx.accounts.yield_vault;
+        vault.total_assets =
+            vault.total_assets.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        let burned = shares.checked_sub(fee_shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        vault.total_shares =
+            vault.total_shares.checked_sub(burned).ok_or(YieldAggregatorError::MathOverflow)?;
+        vault.fee_shares =
+            vault.fee_shares.checked_add(fee_shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        ctx.accounts.yield_aggregator.fees_collected.record(FeeType::Withdrawal, fee)?;
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        protocol_info.tvl =
+            protocol_info.tvl.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        let aggregator = &mut ctx.accounts.yield_aggregator;
+        aggregator.total_tvl =
+            aggregator.total_tvl.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+
         let timestamp = Clock::get()?.unix_timestamp;
-        ctx.accounts.user_position.last_activity = timestamp;
+        let user_key = ctx.accounts.user.key();
+
+        let user_position = &mut ctx.accounts.user_position;
+        user_position.accrue(ctx.accounts.protocol_info.yield_index)?;
+        user_position.debit(shares)?;
+        user_position.user = user_key;
+        user_position.protocol = protocol_key;
+        user_position.last_activity = timestamp;
+        user_position.bump = ctx.bumps.user_position;
+        let closed = user_position.shares == 0;
+
+        let position_key = ctx.accounts.user_position.key();
+        let user_summary = &mut ctx.accounts.user_summary;
+        if closed {
+            user_summary.untrack(&position_key);
+        }
+        user_summary.user = user_key;
+        user_summary.last_activity = timestamp;
+        user_summary.bump = ctx.bumps.user_summary;
 
         Ok(())
     }
 }
 
+// ============================== Preview Vault ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PreviewVaultParams {
+    pub protocol_name: String,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: PreviewVaultParams)]
+pub struct PreviewVault<'info> {
+    #[account(
+        seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
+        bump = protocol_info.bump
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    #[account(
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump = yield_vault.bump
+    )]
+    pub yield_vault: Account<'info, YieldVault>,
+    #[account(
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump
+    )]
+    pub yield_aggregator: Account<'info, YieldAggregator>,
+}
+
+impl PreviewVault<'_> {
+    /// The vault as it would look after accruing yield and fees to the current time.
+    fn synced_vault(ctx: &Context<Self>) -> Result<YieldVault> {
+        let now = Clock::get()?.unix_timestamp;
+        let index = ctx.accounts.protocol_info.projected_index(now)?;
+        let mut vault = YieldVault::clone(&ctx.accounts.yield_vault);
+        vault.sync(index)?;
+        vault.accrue_fees(&ctx.accounts.yield_aggregator.fee_config, now)?;
+        Ok(vault)
+    }
+
+    pub fn preview_deposit(ctx: &Context<Self>, params: &PreviewVaultParams) -> Result<u64> {
+        let bps = ctx.accounts.yield_aggregator.fee_config.deposit_bps;
+        Ok(Self::synced_vault(ctx)?.quote_deposit(params.amount, bps)?.shares)
+    }
+
+    pub fn preview_mint(ctx: &Context<Self>, params: &PreviewVaultParams) -> Result<u64> {
+        let bps = ctx.accounts.yield_aggregator.fee_config.deposit_bps;
+        Ok(Self::synced_vault(ctx)?.quote_mint(params.amount, bps)?.assets)
+    }
+
+    pub fn preview_withdraw(ctx: &Context<Self>, params: &PreviewVaultParams) -> Result<u64> {
+        let bps = ctx.accounts.yield_aggregator.fee_config.withdrawal_bps;
+        Ok(Self::synced_vault(ctx)?.quote_withdraw(params.amount, bps)?.shares)
+    }
+
+    pub fn preview_redeem(ctx: &Context<Self>, params: &PreviewVaultParams) -> Result<u64> {
+        let bps = ctx.accounts.yield_aggregator.fee_config.withdrawal_bps;
+        Ok(Self::synced_vault(ctx)?.quote_redeem(params.amount, bps)?.assets)
+    }
+}
+
 // ============================== Rebalance Position ==============================
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -215,41 +971,233 @@ pub struct RebalancePositionParams {
 pub struct RebalancePosition<'info> {
     #[account(
         mut,
-        seeds = [USER_POSITION_SEED, user.key().as_ref()],
-        bump = user_position.bump,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), from_protocol.key().as_ref()],
+        bump = from_position.bump,
         has_one = user @ YieldAggregatorError::Unauthorized
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub from_position: Box<Account<'info, UserPosition>>,
     #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPosition::INIT_SPACE,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), to_protocol.key().as_ref()],
+        bump
+    )]
+    pub to_position: Box<Account<'info, UserPosition>>,
+    #[account(
+        mut,
+        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        bump = user_summary.bump,
+        has_one = user @ YieldAggregatorError::Unauthorized
+    )]
+    pub user_summary: Box<Account<'info, UserSummary>>,
+    #[account(
+        mut,
         seeds = [PROTOCOL_SEED, params.from_protocol.as_bytes()],
         bump = from_protocol.bump,
         constraint = from_protocol.is_active @ YieldAggregatorError::ProtocolInactive
     )]
-    pub from_protocol: Account<'info, ProtocolInfo>,
+    pub from_protocol: Box<Account<'info, ProtocolInfo>>,
     #[account(
+        mut,
         seeds = [PROTOCOL_SEED, params.to_protocol.as_bytes()],
         bump = to_protocol.bump,
         constraint = to_protocol.is_active @ YieldAggregatorError::ProtocolInactive
     )]
-    pub to_protocol: Account<'info, ProtocolInfo>,
+    pub to_protocol: Box<Account<'info, ProtocolInfo>>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, from_protocol.key().as_ref()],
+        bump = from_vault.bump,
+        has_one = mint,
+        constraint = from_vault.receipt_mint == from_receipt_mint.key()
+    )]
+    pub from_vault: Box<Account<'info, YieldVault>>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, to_protocol.key().as_ref()],
+        bump = to_vault.bump,
+        has_one = mint @ YieldAggregatorError::VaultMintMismatch,
+        constraint = to_vault.receipt_mint == to_receipt_mint.key()
+    )]
+    pub to_vault: Box<Account<'info, YieldVault>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = from_vault
+    )]
+    pub from_vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = to_vault
+    )]
+    pub to_vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = from_receipt_mint,
+        token::authority = user
+    )]
+    pub user_from_receipt_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = to_receipt_mint,
+        associated_token::authority = user
+    )]
+    pub user_to_receipt_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub from_receipt_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub to_receipt_mint: Box<Account<'info, Mint>>,
     #[account(
         mut,
         seeds = [YIELD_AGGREGATOR_SEED],
         bump = yield_aggregator.bump,
         constraint = !yield_aggregator.emergency_paused @ YieldAggregatorError::EmergencyPaused
     )]
-    pub yield_aggregator: Account<'info, YieldAggregator>,
+    pub yield_aggregator: Box<Account<'info, YieldAggregator>>,
     #[account(mut)]
     pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl RebalancePosition<'_> {
+    /// Moves `params.amount` assets out of the source vault and into the destination vault,
+    /// burning source receipt tokens and minting destination ones at each vault's own rate.
     pub fn apply(ctx: &mut Context<Self>, params: &RebalancePositionParams) -> Result<()> {
         require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
+        require!(
+            ctx.accounts.from_protocol.key() != ctx.accounts.to_protocol.key(),
+            YieldAggregatorError::InvalidProtocolName
+        );
+
+        let from_index = accrue_protocol(
+            &mut ctx.accounts.from_protocol,
+            &mut ctx.accounts.from_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+        let to_index = accrue_protocol(
+            &mut ctx.accounts.to_protocol,
+            &mut ctx.accounts.to_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+
+        // The rebalance fee is charged on top of the moved assets and stays in the source vault
+        let assets = params.amount;
+        let bps = ctx.accounts.yield_aggregator.fee_config.rebalance_bps;
+        let exit = ctx.accounts.from_vault.quote_withdraw(assets, bps)?;
+        let shares_out = exit.shares;
+        let shares_in = ctx.accounts.to_vault.quote_deposit(assets, 0)?.shares;
+        require!(shares_in > 0, YieldAggregatorError::InvalidAmount);
+        ctx.accounts.to_protocol.check_capacity(assets)?;
+        require!(
+            shares_out <= ctx.accounts.user_from_receipt_account.amount
+                && assets <= ctx.accounts.from_vault.total_assets,
+            YieldAggregatorError::InsufficientBalance
+        );
+        require!(
+            assets <= ctx.accounts.from_vault_token_account.amount,
+            YieldAggregatorError::InsufficientLiquidity
+        );
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.from_receipt_mint.to_account_info(),
+                    from: ctx.accounts.user_from_receipt_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares_out,
+        )?;
+
+        let from_key = ctx.accounts.from_protocol.key();
+        let from_seeds: &[&[u8]] =
+            &[YIELD_VAULT_SEED, from_key.as_ref(), &[ctx.accounts.from_vault.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.from_vault_token_account.to_account_info(),
+                    to: ctx.accounts.to_vault_token_account.to_account_info(),
+                    authority: ctx.accounts.from_vault.to_account_info(),
+                },
+                &[from_seeds],
+            ),
+            assets,
+        )?;
+
+        let to_key = ctx.accounts.to_protocol.key();
+        let to_seeds: &[&[u8]] =
+            &[YIELD_VAULT_SEED, to_key.as_ref(), &[ctx.accounts.to_vault.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.to_receipt_mint.to_account_info(),
+                    to: ctx.accounts.user_to_receipt_account.to_account_info(),
+                    authority: ctx.accounts.to_vault.to_account_info(),
+                },
+                &[to_seeds],
+            ),
+            shares_in,
+        )?;
+
+        let from_vault = &mut ctx.accounts.from_vault;
+        from_vault.total_assets =
+            from_vault.total_assets.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        let burned =
+            shares_out.checked_sub(exit.fee_shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        from_vault.total_shares =
+            from_vault.total_shares.checked_sub(burned).ok_or(YieldAggregatorError::MathOverflow)?;
+        from_vault.fee_shares = from_vault
+            .fee_shares
+            .checked_add(exit.fee_shares)
+            .ok_or(YieldAggregatorError::MathOverflow)?;
+        ctx.accounts.yield_aggregator.fees_collected.record(FeeType::Rebalance, exit.fee)?;
+        let to_vault = &mut ctx.accounts.to_vault;
+        to_vault.total_assets =
+            to_vault.total_assets.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        to_vault.total_shares =
+            to_vault.total_shares.checked_add(shares_in).ok_or(YieldAggregatorError::MathOverflow)?;
+        let from_protocol = &mut ctx.accounts.from_protocol;
+        from_protocol.tvl =
+            from_protocol.tvl.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        let to_protocol = &mut ctx.accounts.to_protocol;
+        to_protocol.tvl =
+            to_protocol.tvl.checked_add(assets).ok_or(YieldAggregatorError::MathOverflow)?;
 
         let timestamp = Clock::get()?.unix_timestamp;
-        ctx.accounts.user_position.last_activity = timestamp;
+        let user_key = ctx.accounts.user.key();
+
+        // Cost basis follows the funds so yield keeps being measured against the original deposit
+        let from_position = &mut ctx.accounts.from_position;
+        from_position.accrue(from_index)?;
+        let principal = from_position.debit(shares_out)?;
+        from_position.last_activity = timestamp;
+        let from_closed = from_position.shares == 0;
+
+        let to_position = &mut ctx.accounts.to_position;
+        to_position.user = user_key;
+        to_position.protocol = to_key;
+        to_position.accrue(to_index)?;
+        to_position.credit(principal, shares_in, ctx.accounts.to_protocol.current_apy)?;
+        to_position.last_activity = timestamp;
+        to_position.bump = ctx.bumps.to_position;
+
+        let from_position_key = ctx.accounts.from_position.key();
+        let to_position_key = ctx.accounts.to_position.key();
+        let user_summary = &mut ctx.accounts.user_summary;
+        if from_closed {
+            user_summary.untrack(&from_position_key);
+        }
+        user_summary.track(to_position_key)?;
+        user_summary.last_activity = timestamp;
 
         Ok(())
     }
@@ -276,7 +1224,7 @@ pub struct UpdateYieldRates<'info> {
         mut,
         seeds = [YIELD_AGGREGATOR_SEED],
         bump = yield_aggregator.bump,
-        has_one = admin @ YieldAggregatorError::Unauthorized
+        constraint = yield_aggregator.is_authority(&admin.key()) @ YieldAggregatorError::Unauthorized
     )]
     pub yield_aggregator: Account<'info, YieldAggregator>,
     #[account(mut)]
@@ -287,8 +1235,10 @@ pub struct UpdateYieldRates<'info> {
 impl UpdateYieldRates<'_> {
     pub fn apply(ctx: &mut Context<Self>, params: &UpdateYieldRatesParams) -> Result<()> {
         let timestamp = Clock::get()?.unix_timestamp;
-        
+
+        // Close out the elapsed period at the old rate before switching
         let protocol = &mut ctx.accounts.protocol_info;
+        protocol.accrue(timestamp)?;
         protocol.current_apy = params.new_apy;
         protocol.last_update = timestamp;
 
@@ -308,17 +1258,24 @@ pub struct CompoundYieldParams {
 pub struct CompoundYield<'info> {
     #[account(
         mut,
-        seeds = [USER_POSITION_SEED, user.key().as_ref()],
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), protocol_info.key().as_ref()],
         bump = user_position.bump,
         has_one = user @ YieldAggregatorError::Unauthorized
     )]
     pub user_position: Account<'info, UserPosition>,
     #[account(
+        mut,
         seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
         bump = protocol_info.bump,
         constraint = protocol_info.is_active @ YieldAggregatorError::ProtocolInactive
     )]
     pub protocol_info: Account<'info, ProtocolInfo>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump = yield_vault.bump
+    )]
+    pub yield_vault: Account<'info, YieldVault>,
     #[account(
         mut,
         seeds = [YIELD_AGGREGATOR_SEED],
@@ -332,21 +1289,136 @@ pub struct CompoundYield<'info> {
 }
 
 impl CompoundYield<'_> {
-    pub fn apply(ctx: &mut Context<Self>, params: &CompoundYieldParams) -> Result<()> {
+    pub fn apply(ctx: &mut Context<Self>, _params: &CompoundYieldParams) -> Result<()> {
+        let timestamp = Clock::get()?.unix_timestamp;
+        // Position yield is tracked from the index; compounding only moves it into principal
+        let index = accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+
         let user_position = &mut ctx.accounts.user_position;
-        require!(user_position.total_yield_earned > 0, YieldAggregatorError::NoYieldToCompound);
+        user_position.accrue(index)?;
+        require!(user_position.accrued_yield > 0, YieldAggregatorError::NoYieldToCompound);
 
-        let timestamp = Clock::get()?.unix_timestamp;
-        let yield_amount = user_position.total_yield_earned;
-        
-        user_position.total_deposits += yield_amount;
-        user_position.total_yield_earned = 0;
+        let yield_amount = user_position.accrued_yield;
+
+        user_position.principal = user_position
+            .principal
+            .checked_add(yield_amount)
+            .ok_or(YieldAggregatorError::MathOverflow)?;
+        user_position.accrued_yield = 0;
         user_position.last_activity = timestamp;
 
         Ok(())
     }
 }
 
+// ============================== Harvest Yield ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct HarvestYieldParams {
+    pub protocol_name: String,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: HarvestYieldParams)]
+pub struct HarvestYield<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
+        bump = protocol_info.bump
+    )]
+    pub protocol_info: Box<Account<'info, ProtocolInfo>>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump = yield_vault.bump,
+        has_one = mint
+    )]
+    pub yield_vault: Box<Account<'info, YieldVault>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = yield_vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin
+    )]
+    pub admin_token_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        has_one = admin @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_aggregator: Box<Account<'info, YieldAggregator>>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl HarvestYield<'_> {
+    /// Moves yield realized by the protocol into the vault and books it, so the share price only
+    /// rises with tokens behind it. At most the index yield owed to the vault is taken.
+    pub fn apply(ctx: &mut Context<Self>, params: &HarvestYieldParams) -> Result<()> {
+        require!(params.amount > 0, YieldAggregatorError::InvalidAmount);
+
+        accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+        let amount = params.amount.min(ctx.accounts.yield_vault.unharvested_yield);
+        require!(amount > 0, YieldAggregatorError::NoYieldToHarvest);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        // Fees are taken on the harvested gain before anyone enters or exits at the new price
+        let now = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.yield_vault;
+        vault.harvest(amount)?;
+        let fees = vault.accrue_fees(&ctx.accounts.yield_aggregator.fee_config, now)?;
+        let unharvested_yield = vault.unharvested_yield;
+
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        protocol_info.tvl =
+            protocol_info.tvl.checked_add(amount).ok_or(YieldAggregatorError::MathOverflow)?;
+        let aggregator = &mut ctx.accounts.yield_aggregator;
+        aggregator.total_tvl =
+            aggregator.total_tvl.checked_add(amount).ok_or(YieldAggregatorError::MathOverflow)?;
+        aggregator.total_yield_earned = aggregator
+            .total_yield_earned
+            .checked_add(amount)
+            .ok_or(YieldAggregatorError::MathOverflow)?;
+        aggregator.fees_collected.add(&fees)?;
+
+        emit!(YieldHarvested {
+            protocol: ctx.accounts.protocol_info.key(),
+            amount,
+            unharvested_yield,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
 // ============================== Emergency Pause ==============================
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -360,7 +1432,7 @@ pub struct EmergencyPause<'info> {
         mut,
         seeds = [YIELD_AGGREGATOR_SEED],
         bump = yield_aggregator.bump,
-        has_one = admin @ YieldAggregatorError::Unauthorized
+        constraint = yield_aggregator.is_authority(&admin.key()) @ YieldAggregatorError::Unauthorized
     )]
     pub yield_aggregator: Account<'info, YieldAggregator>,
     #[account(mut)]
@@ -374,3 +1446,224 @@ impl EmergencyPause<'_> {
         Ok(())
     }
 }
+
+// ============================== Set Fee ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetFeeParams {
+    pub fee_type: FeeType,
+    pub bps: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    #[account(
+        mut,
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        constraint = yield_aggregator.is_authority(&admin.key()) @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_aggregator: Account<'info, YieldAggregator>,
+    pub admin: Signer<'info>,
+}
+
+impl SetFee<'_> {
+    pub fn apply(ctx: &mut Context<Self>, params: &SetFeeParams) -> Result<()> {
+        ctx.accounts.yield_aggregator.fee_config.set(params.fee_type, params.bps)
+    }
+}
+
+// ============================== Claim Fees ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimFeesParams {
+    pub protocol_name: String,
+}
+
+#[derive(Accounts)]
+#[instruction(params: ClaimFeesParams)]
+pub struct ClaimFees<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
+        bump = protocol_info.bump
+    )]
+    pub protocol_info: Box<Account<'info, ProtocolInfo>>,
+    #[account(
+        mut,
+        seeds = [YIELD_VAULT_SEED, protocol_info.key().as_ref()],
+        bump = yield_vault.bump,
+        has_one = mint
+    )]
+    pub yield_vault: Box<Account<'info, YieldVault>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = yield_vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = yield_aggregator.fee_recipient
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        constraint = authority.key() == yield_aggregator.admin
+            || authority.key() == yield_aggregator.fee_recipient @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_aggregator: Box<Account<'info, YieldAggregator>>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl ClaimFees<'_> {
+    /// Redeems the vault's fee shares and pays them out to the fee recipient. Only liquid
+    /// tokens can be paid, so a claim against an under-harvested vault is partial.
+    pub fn apply(ctx: &mut Context<Self>, _params: &ClaimFeesParams) -> Result<()> {
+        accrue_protocol(
+            &mut ctx.accounts.protocol_info,
+            &mut ctx.accounts.yield_vault,
+            &mut ctx.accounts.yield_aggregator,
+        )?;
+
+        let vault = &ctx.accounts.yield_vault;
+        let mut shares = vault.fee_shares;
+        let mut assets = vault.convert_to_assets(shares, Rounding::Down)?;
+        let liquid = ctx.accounts.vault_token_account.amount;
+        if assets > liquid {
+            assets = liquid;
+            shares = vault.convert_to_shares(assets, Rounding::Up)?.min(vault.fee_shares);
+        }
+        require!(assets > 0, YieldAggregatorError::NoFeesToClaim);
+
+        let protocol_key = ctx.accounts.protocol_info.key();
+        let seeds: &[&[u8]] =
+            &[YIELD_VAULT_SEED, protocol_key.as_ref(), &[ctx.accounts.yield_vault.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.yield_vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            assets,
+        )?;
+
+        let vault = &mut ctx.accounts.yield_vault;
+        vault.total_assets =
+            vault.total_assets.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        vault.total_shares =
+            vault.total_shares.checked_sub(shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        vault.fee_shares =
+            vault.fee_shares.checked_sub(shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        protocol_info.tvl =
+            protocol_info.tvl.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+
+        let aggregator = &mut ctx.accounts.yield_aggregator;
+        aggregator.total_tvl =
+            aggregator.total_tvl.checked_sub(assets).ok_or(YieldAggregatorError::MathOverflow)?;
+        aggregator.total_fees_claimed = aggregator
+            .total_fees_claimed
+            .checked_add(assets)
+            .ok_or(YieldAggregatorError::MathOverflow)?;
+
+        emit!(FeesClaimed {
+            protocol: protocol_key,
+            recipient: aggregator.fee_recipient,
+            shares,
+            assets,
+            lifetime_fees: aggregator.fees_collected,
+            total_fees_claimed: aggregator.total_fees_claimed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+// ============================== Set Remote Authority ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetRemoteAuthorityParams {
+    pub remote_authority: Pubkey,
+    pub remote_admin: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct SetRemoteAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        has_one = admin @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_aggregator: Account<'info, YieldAggregator>,
+    pub admin: Signer<'info>,
+}
+
+impl SetRemoteAuthority<'_> {
+    /// Records the cross-chain messaging program's signers: the remote authority that holds
+    /// remote users' funds and the remote admin that may act on rate, config, fee and pause
+    /// instructions. Setting the default pubkey revokes either.
+    pub fn apply(ctx: &mut Context<Self>, params: &SetRemoteAuthorityParams) -> Result<()> {
+        let aggregator = &mut ctx.accounts.yield_aggregator;
+        aggregator.remote_authority = params.remote_authority;
+        aggregator.remote_admin = params.remote_admin;
+        Ok(())
+    }
+}
+
+// ============================== Update Protocol Config ==============================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateProtocolConfigParams {
+    pub protocol_name: String,
+    pub max_capacity: Option<u64>,
+    pub risk_score: Option<u8>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: UpdateProtocolConfigParams)]
+pub struct UpdateProtocolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, params.protocol_name.as_bytes()],
+        bump = protocol_info.bump
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    #[account(
+        seeds = [YIELD_AGGREGATOR_SEED],
+        bump = yield_aggregator.bump,
+        constraint = yield_aggregator.is_authority(&admin.key()) @ YieldAggregatorError::Unauthorized
+    )]
+    pub yield_aggregator: Account<'info, YieldAggregator>,
+    pub admin: Signer<'info>,
+}
+
+impl UpdateProtocolConfig<'_> {
+    pub fn apply(ctx: &mut Context<Self>, params: &UpdateProtocolConfigParams) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol_info;
+        if let Some(max_capacity) = params.max_capacity {
+            protocol.max_capacity = max_capacity;
+        }
+        if let Some(risk_score) = params.risk_score {
+            require!((1..=10).contains(&risk_score), YieldAggregatorError::InvalidRiskScore);
+            protocol.risk_score = risk_score;
+        }
+        if let Some(is_active) = params.is_active {
+            protocol.is_active = is_active;
+        }
+        protocol.last_update = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::error::YieldAggregatorError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Computes `a * b / denominator` in 128-bit precision with the requested rounding.
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u64> {
+    require!(denominator > 0, YieldAggregatorError::MathOverflow);
+
+    let product = a.checked_mul(b).ok_or(YieldAggregatorError::MathOverflow)?;
+    let mut result = product / denominator;
+    if rounding == Rounding::Up && product % denominator != 0 {
+        result += 1;
+    }
+
+    u64::try_from(result).map_err(|_| YieldAggregatorError::MathOverflow.into())
+}
+
+/// Advances a cumulative yield index by `apy_bps` (simple interest) over `elapsed` seconds.
+/// Compounding happens naturally because every accrual grows the index it started from.
+pub fn accrue_index(index: u128, apy_bps: u64, elapsed: u64) -> Result<u128> {
+    let growth = index
+        .checked_mul(apy_bps as u128)
+        .and_then(|v| v.checked_mul(elapsed as u128))
+        .ok_or(YieldAggregatorError::MathOverflow)?
+        / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+    index.checked_add(growth).ok_or(YieldAggregatorError::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::INDEX_SCALE;
+    use crate::state::YieldVault;
+
+    #[test]
+    fn test_mul_div_rounding() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up).unwrap(), 4);
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up).unwrap(), 3);
+        assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+        assert!(mul_div(u64::MAX as u128, 2, 1, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn test_empty_vault_mints_one_to_one() {
+        let vault = YieldVault::for_test(0, 0);
+        assert_eq!(vault.preview_deposit(1_000).unwrap(), 1_000);
+        assert_eq!(vault.preview_mint(1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_previews_round_in_favor_of_vault() {
+        // 1_000 shares backed by 1_500 assets after yield accrued
+        let vault = YieldVault::for_test(1_000, 1_500);
+
+        let shares = vault.preview_deposit(100).unwrap();
+        assert!(vault.preview_mint(shares).unwrap() <= 100);
+
+        let assets = vault.preview_redeem(shares).unwrap();
+        assert!(assets <= 100);
+
+        let burned = vault.preview_withdraw(100).unwrap();
+        assert!(burned >= shares);
+        assert!(vault.preview_redeem(burned).unwrap() >= 100);
+    }
+
+    #[test]
+    fn test_late_depositor_does_not_capture_earlier_yield() {
+        // Share price doubled before the second deposit, so the same assets buy half the shares
+        let vault = YieldVault::for_test(1_000, 2_000);
+        let shares = vault.preview_deposit(2_000).unwrap();
+        assert_eq!(shares, 1_000);
+    }
+
+    #[test]
+    fn test_index_yield_waits_for_harvest() {
+        let mut vault = YieldVault::for_test(1_000, 1_000);
+
+        // 10% index growth is owed to the vault but does not move the share price
+        vault.sync(INDEX_SCALE * 11 / 10).unwrap();
+        assert_eq!(vault.total_assets, 1_000);
+        assert_eq!(vault.unharvested_yield, 100);
+        assert_eq!(vault.preview_redeem(1_000).unwrap(), 1_000);
+
+        // Funding books at most what is owed
+        assert_eq!(vault.harvest(60).unwrap(), 60);
+        assert_eq!(vault.harvest(100).unwrap(), 40);
+        assert_eq!(vault.total_assets, 1_100);
+        assert_eq!(vault.unharvested_yield, 0);
+        assert_eq!(vault.preview_redeem(1_000).unwrap(), 1_099);
+    }
+
+    #[test]
+    fn test_accrue_index_one_year() {
+        // 10% APY for a full year grows the index by exactly 10%
+        let index = accrue_index(INDEX_SCALE, 1_000, SECONDS_PER_YEAR).unwrap();
+        assert_eq!(index, INDEX_SCALE * 11 / 10);
+    }
+
+    #[test]
+    fn test_accrue_index_across_rate_change() {
+        // Half a year at 10%, then half a year at 20%
+        let half = SECONDS_PER_YEAR / 2;
+        let index = accrue_index(INDEX_SCALE, 1_000, half).unwrap();
+        let index = accrue_index(index, 2_000, half).unwrap();
+        assert_eq!(index, INDEX_SCALE * 105 / 100 * 110 / 100);
+
+        // Zero elapsed time is a no-op
+        assert_eq!(accrue_index(index, 2_000, 0).unwrap(), index);
+    }
+}
@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::error::YieldAggregatorError;
+use crate::fees::{FeeConfig, FeeTotals};
+use crate::math::{accrue_index, mul_div, Rounding};
 
 #[account]
 #[derive(InitSpace)]
@@ -9,12 +12,23 @@ pub struct YieldAggregator {
     pub total_yield_earned: u64,
     pub emergency_paused: bool,
     pub bump: u8,
-    pub fee_rate: u64,
+    pub fee_config: FeeConfig,
     pub fee_recipient: Pubkey,
+    pub fees_collected: FeeTotals, // Lifetime fees charged, in asset terms
+    pub total_fees_claimed: u64,
+    pub remote_authority: Pubkey, // Cross-chain custody signer, a plain vault user; default when unset
+    pub remote_admin: Pubkey, // Signer of guardian-verified cross-chain admin messages, default when unset
 }
 
 impl YieldAggregator {
-    pub const SIZE: usize = 8 + 32 + 4 + 8 + 8 + 1 + 1 + 8 + 32; // discriminator + fields
+    pub const SIZE: usize = 8 + Self::INIT_SPACE; // discriminator + fields
+
+    /// Admin actions can also arrive as guardian-verified cross-chain messages through the
+    /// remote admin. The remote authority only deposits, withdraws and rebalances for remote
+    /// users, and has no admin rights.
+    pub fn is_authority(&self, key: &Pubkey) -> bool {
+        *key == self.admin || (self.remote_admin != Pubkey::default() && *key == self.remote_admin)
+    }
 }
 
 #[account]
@@ -29,25 +43,242 @@ pub struct ProtocolInfo {
     pub risk_score: u8, // 1-10 scale
     pub is_active: bool,
     pub last_update: i64,
+    pub yield_index: u128, // Cumulative growth of one unit deposited at launch, INDEX_SCALE fixed point
+    pub last_accrual: i64,
     pub bump: u8,
 }
 
+impl ProtocolInfo {
+    /// Index value at `now` without mutating state, for read-only previews.
+    pub fn projected_index(&self, now: i64) -> Result<u128> {
+        let elapsed = now.saturating_sub(self.last_accrual).max(0) as u64;
+        accrue_index(self.yield_index, self.current_apy, elapsed)
+    }
+
+    /// Rolls the index forward to `now` at the current APY. Must run before `current_apy` changes
+    /// so each period accrues at the rate that was in force during it.
+    pub fn accrue(&mut self, now: i64) -> Result<u128> {
+        self.yield_index = self.projected_index(now)?;
+        self.last_accrual = self.last_accrual.max(now);
+        Ok(self.yield_index)
+    }
+
+    /// Deposits the protocol can still take before hitting `max_capacity`.
+    pub fn headroom(&self) -> u64 {
+        self.max_capacity.saturating_sub(self.tvl)
+    }
+
+    pub fn check_capacity(&self, additional: u64) -> Result<()> {
+        let new_tvl = self.tvl.checked_add(additional).ok_or(YieldAggregatorError::MathOverflow)?;
+        require!(new_tvl <= self.max_capacity, YieldAggregatorError::ProtocolCapacityExceeded);
+        Ok(())
+    }
+}
+
+pub const MAX_USER_POSITIONS: usize = 16;
+
+/// A user's stake in a single protocol, keyed by `(user, protocol)`.
 #[account]
 #[derive(InitSpace)]
 pub struct UserPosition {
     pub user: Pubkey,
-    pub total_deposits: u64,
-    pub total_yield_earned: u64,
-    pub position_count: u32,
+    pub protocol: Pubkey,
+    pub principal: u64,
+    pub shares: u64,
+    pub entry_apy: u64, // Principal-weighted APY at deposit time, basis points
+    pub accrued_yield: u64,
+    pub index_snapshot: u128, // Protocol yield_index when accrued_yield was last brought up to date
+    pub last_activity: i64,
+    pub bump: u8,
+}
+
+impl UserPosition {
+    /// Credits yield earned on principal since the last snapshot and re-snapshots at `index`.
+    /// Must run before principal changes.
+    pub fn accrue(&mut self, index: u128) -> Result<u64> {
+        let mut earned = 0;
+        if self.index_snapshot > 0 && index > self.index_snapshot {
+            earned = mul_div(
+                self.principal as u128,
+                index - self.index_snapshot,
+                self.index_snapshot,
+                Rounding::Down,
+            )?;
+            self.accrued_yield =
+                self.accrued_yield.checked_add(earned).ok_or(YieldAggregatorError::MathOverflow)?;
+        }
+        self.index_snapshot = index;
+        Ok(earned)
+    }
+
+    /// Records `principal` entering the position for `shares`, blending the entry APY.
+    pub fn credit(&mut self, principal: u64, shares: u64, apy: u64) -> Result<()> {
+        let new_principal =
+            self.principal.checked_add(principal).ok_or(YieldAggregatorError::MathOverflow)?;
+        if new_principal > 0 {
+            let weighted = (self.entry_apy as u128 * self.principal as u128)
+                .checked_add(apy as u128 * principal as u128)
+                .ok_or(YieldAggregatorError::MathOverflow)?;
+            self.entry_apy = u64::try_from(weighted / new_principal as u128)
+                .map_err(|_| YieldAggregatorError::MathOverflow)?;
+        }
+        self.principal = new_principal;
+        self.shares = self.shares.checked_add(shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Removes up to `shares` from the position and returns the principal released with them.
+    /// Shares received by receipt token transfer were never recorded here and release nothing.
+    pub fn debit(&mut self, shares: u64) -> Result<u64> {
+        let tracked_shares = shares.min(self.shares);
+        if tracked_shares == 0 {
+            return Ok(0);
+        }
+        let principal = mul_div(
+            self.principal as u128,
+            tracked_shares as u128,
+            self.shares as u128,
+            Rounding::Up,
+        )?
+        .min(self.principal);
+        self.principal =
+            self.principal.checked_sub(principal).ok_or(YieldAggregatorError::MathOverflow)?;
+        self.shares =
+            self.shares.checked_sub(tracked_shares).ok_or(YieldAggregatorError::MathOverflow)?;
+        Ok(principal)
+    }
+}
+
+/// Lists every open per-protocol position of a user.
+#[account]
+#[derive(InitSpace)]
+pub struct UserSummary {
+    pub user: Pubkey,
+    #[max_len(MAX_USER_POSITIONS)]
+    pub positions: Vec<Pubkey>,
     pub last_activity: i64,
     pub bump: u8,
 }
 
+impl UserSummary {
+    pub fn track(&mut self, position: Pubkey) -> Result<()> {
+        if !self.positions.contains(&position) {
+            require!(
+                self.positions.len() < MAX_USER_POSITIONS,
+                YieldAggregatorError::TooManyPositions
+            );
+            self.positions.push(position);
+        }
+        Ok(())
+    }
+
+    pub fn untrack(&mut self, position: &Pubkey) {
+        self.positions.retain(|p| p != position);
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct YieldVault {
+    pub protocol: Pubkey,
     pub mint: Pubkey,
-    pub authority: Pubkey,
-    pub total_deposits: u64,
+    pub authority: Pubkey, // Admin that created the vault; token custody is held by the vault PDA
+    pub receipt_mint: Pubkey, // Share token, minted and burned by the vault PDA
+    pub total_shares: u64,
+    pub total_assets: u64,
+    pub last_index: u128, // Protocol yield_index the vault was last synced to
+    pub unharvested_yield: u64, // Index yield owed to the vault that no tokens back yet
+    pub fee_shares: u64, // Part of total_shares owed to the fee recipient
+    pub high_water_mark: u128, // Highest share price performance fees were charged at
+    pub last_fee_accrual: i64,
     pub bump: u8,
-}
\ No newline at end of file
+}
+
+// ERC-4626 conversions. The +1 virtual share/asset keeps an empty vault at a 1:1 rate and makes
+// donation-based share price inflation unprofitable. Previews always round in favor of the vault.
+impl YieldVault {
+    /// Records the yield the protocol index accrued on total_assets since the last sync. It is
+    /// owed to the vault but stays out of total_assets, and so out of the share price, until
+    /// `harvest` books tokens that were moved in for it.
+    pub fn sync(&mut self, index: u128) -> Result<()> {
+        if self.last_index > 0 && index > self.last_index {
+            let grown = mul_div(self.total_assets as u128, index, self.last_index, Rounding::Down)?;
+            self.unharvested_yield = self
+                .unharvested_yield
+                .checked_add(grown - self.total_assets)
+                .ok_or(YieldAggregatorError::MathOverflow)?;
+        }
+        self.last_index = index;
+        Ok(())
+    }
+
+    /// Books up to `amount` of funded yield into total_assets, which raises the share price for
+    /// every holder at once. Returns the amount booked, capped at what the index says is owed.
+    pub fn harvest(&mut self, amount: u64) -> Result<u64> {
+        let booked = amount.min(self.unharvested_yield);
+        self.total_assets =
+            self.total_assets.checked_add(booked).ok_or(YieldAggregatorError::MathOverflow)?;
+        self.unharvested_yield =
+            self.unharvested_yield.checked_sub(booked).ok_or(YieldAggregatorError::MathOverflow)?;
+        Ok(booked)
+    }
+
+    pub fn convert_to_shares(&self, assets: u64, rounding: Rounding) -> Result<u64> {
+        mul_div(
+            assets as u128,
+            self.total_shares as u128 + 1,
+            self.total_assets as u128 + 1,
+            rounding,
+        )
+    }
+
+    pub fn convert_to_assets(&self, shares: u64, rounding: Rounding) -> Result<u64> {
+        mul_div(
+            shares as u128,
+            self.total_assets as u128 + 1,
+            self.total_shares as u128 + 1,
+            rounding,
+        )
+    }
+
+    /// Shares minted for depositing `assets`
+    pub fn preview_deposit(&self, assets: u64) -> Result<u64> {
+        self.convert_to_shares(assets, Rounding::Down)
+    }
+
+    /// Assets required to mint exactly `shares`
+    pub fn preview_mint(&self, shares: u64) -> Result<u64> {
+        self.convert_to_assets(shares, Rounding::Up)
+    }
+
+    /// Shares burned to withdraw exactly `assets`
+    pub fn preview_withdraw(&self, assets: u64) -> Result<u64> {
+        self.convert_to_shares(assets, Rounding::Up)
+    }
+
+    /// Assets returned for redeeming `shares`
+    pub fn preview_redeem(&self, shares: u64) -> Result<u64> {
+        self.convert_to_assets(shares, Rounding::Down)
+    }
+}
+
+#[cfg(test)]
+impl YieldVault {
+    /// Vault holding `total_assets` for `total_shares`, synced to the initial index.
+    pub fn for_test(total_shares: u64, total_assets: u64) -> Self {
+        YieldVault {
+            protocol: Pubkey::default(),
+            mint: Pubkey::default(),
+            authority: Pubkey::default(),
+            receipt_mint: Pubkey::default(),
+            total_shares,
+            total_assets,
+            last_index: crate::constants::INDEX_SCALE,
+            unharvested_yield: 0,
+            fee_shares: 0,
+            high_water_mark: crate::constants::INDEX_SCALE,
+            last_fee_accrual: 0,
+            bump: 0,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::ProtocolInfo;
+
+pub const MAX_STRATEGY_PROTOCOLS: usize = 8;
+// Rate data older than this counts half as much as fresh data, and keeps decaying after that
+pub const STALENESS_HALF_LIFE: i64 = 24 * 60 * 60;
+
+/// One leg of a ranked allocation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct StrategyAllocation {
+    pub protocol: Pubkey,
+    pub amount: u64,
+    pub apy: u64,
+    pub risk_score: u8,
+    pub headroom: u64,
+    pub staleness: i64, // Seconds since the protocol's rate was last updated
+    pub score: u64, // Risk- and staleness-adjusted APY in basis points
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct Strategy {
+    pub allocations: Vec<StrategyAllocation>,
+    pub unallocated: u64, // Part of the amount no eligible protocol had capacity for
+}
+
+/// APY discounted by risk (risk 1 keeps the full rate, risk 10 keeps a tenth) and by how stale
+/// the rate is.
+pub fn score(protocol: &ProtocolInfo, now: i64) -> u64 {
+    let staleness = now.saturating_sub(protocol.last_update).max(0) as u128;
+    let risk_weight = 11u128.saturating_sub(protocol.risk_score as u128);
+    let score = protocol.current_apy as u128 * risk_weight * STALENESS_HALF_LIFE as u128
+        / (10 * (STALENESS_HALF_LIFE as u128 + staleness));
+    score as u64
+}
+
+/// Ranks the eligible protocols by score and fills them greedily up to each one's capacity.
+/// Ties go to the lower risk score.
+pub fn rank(
+    protocols: &[(Pubkey, &ProtocolInfo)],
+    amount: u64,
+    max_risk_score: u8,
+    min_apy: u64,
+    now: i64,
+) -> Strategy {
+    let mut ranked: Vec<StrategyAllocation> = protocols
+        .iter()
+        .filter(|(_, p)| {
+            p.is_active
+                && p.risk_score <= max_risk_score
+                && p.current_apy >= min_apy
+                && p.headroom() > 0
+        })
+        .map(|(key, p)| StrategyAllocation {
+            protocol: *key,
+            amount: 0,
+            apy: p.current_apy,
+            risk_score: p.risk_score,
+            headroom: p.headroom(),
+            staleness: now.saturating_sub(p.last_update).max(0),
+            score: score(p, now),
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.cmp(&a.score).then(a.risk_score.cmp(&b.risk_score)));
+
+    let mut remaining = amount;
+    for allocation in ranked.iter_mut() {
+        allocation.amount = remaining.min(allocation.headroom);
+        remaining -= allocation.amount;
+    }
+    ranked.retain(|a| a.amount > 0);
+
+    Strategy { allocations: ranked, unallocated: remaining }
+}
+
+/// Splits `amount` by basis point weights. Rounding dust goes to the first leg so the legs
+/// always add up to `amount`.
+pub fn split_by_weights(amount: u64, weights_bps: &[u16]) -> Option<Vec<u64>> {
+    let total: u64 = weights_bps.iter().map(|w| *w as u64).sum();
+    if total != BPS_DENOMINATOR || weights_bps.is_empty() {
+        return None;
+    }
+    let mut legs: Vec<u64> = weights_bps
+        .iter()
+        .map(|w| (amount as u128 * *w as u128 / BPS_DENOMINATOR as u128) as u64)
+        .collect();
+    let dust = amount - legs.iter().sum::<u64>();
+    legs[0] += dust;
+    Some(legs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::INDEX_SCALE;
+
+    fn protocol(apy: u64, risk_score: u8, tvl: u64, max_capacity: u64, last_update: i64) -> ProtocolInfo {
+        ProtocolInfo {
+            name: String::new(),
+            chain_id: 0,
+            current_apy: apy,
+            tvl,
+            max_capacity,
+            risk_score,
+            is_active: true,
+            last_update,
+            yield_index: INDEX_SCALE,
+            last_accrual: last_update,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_score_discounts_risk_and_staleness() {
+        let fresh = protocol(1_000, 1, 0, 1_000, 0);
+        assert_eq!(score(&fresh, 0), 1_000);
+
+        let risky = protocol(1_000, 10, 0, 1_000, 0);
+        assert_eq!(score(&risky, 0), 100);
+
+        assert_eq!(score(&fresh, STALENESS_HALF_LIFE), 500);
+    }
+
+    #[test]
+    fn test_rank_fills_best_score_first_within_capacity() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let safe = protocol(800, 1, 0, 10_000, 0);
+        let full = protocol(2_000, 1, 600, 1_000, 0);
+        let risky = protocol(3_000, 9, 0, 10_000, 0);
+        let protocols = [(a, &safe), (b, &full), (c, &risky)];
+
+        let strategy = rank(&protocols, 5_000, 5, 0, 0);
+        assert_eq!(strategy.unallocated, 0);
+        assert_eq!(strategy.allocations.len(), 2);
+        assert_eq!(strategy.allocations[0].protocol, b);
+        assert_eq!(strategy.allocations[0].amount, 400);
+        assert_eq!(strategy.allocations[1].protocol, a);
+        assert_eq!(strategy.allocations[1].amount, 4_600);
+
+        let strategy = rank(&protocols, 20_000, 5, 0, 0);
+        assert_eq!(strategy.unallocated, 9_600);
+
+        let strategy = rank(&protocols, 5_000, 5, 1_000, 0);
+        assert_eq!(strategy.allocations.len(), 1);
+        assert_eq!(strategy.unallocated, 4_600);
+    }
+
+    #[test]
+    fn test_split_by_weights() {
+        assert_eq!(split_by_weights(1_001, &[5_000, 5_000]), Some(vec![501, 500]));
+        assert_eq!(split_by_weights(1_000, &[5_000, 4_000]), None);
+        assert_eq!(split_by_weights(1_000, &[]), None);
+    }
+}
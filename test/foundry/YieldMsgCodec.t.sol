@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.20;
+
+import { YieldMsgCodec } from "../../contracts/libs/YieldMsgCodec.sol";
+
+// Forge imports
+import { Test } from "forge-std/Test.sol";
+
+/// Payload vectors are the golden vectors of programs/my_oapp/src/abi_codec.rs. Each one is
+/// decoded with the Solidity types of its variant and encoded back from the same values.
+contract YieldMsgCodecTest is Test {
+    uint8 private constant PROTOCOL_CONFIG_TYPE = 8;
+    uint8 private constant YIELD_RATE_CONFIG = 0;
+    uint8 private constant HALT_DEPOSITS = 3;
+
+    // An EVM user as the 32-byte address the Solana program keys positions by
+    bytes32 private constant USER = bytes32(uint256(uint160(0x3333333333333333333333333333333333333333)));
+    bytes32 private constant TOKEN_MINT = 0x4444444444444444444444444444444444444444444444444444444444444444;
+    bytes32 private constant ACKED_ID = 0x2a0772f1e648cc32eff4373bad767edb7648f3b7a8c12121c574d910a1832eb6;
+
+    /// Same vector as `test_message_id_matches_evm_vector` in programs/my_oapp/src/msg_codec.rs
+    function test_messageId_matchesSolana() public {
+        bytes memory payload = abi.encode(
+            uint64(1),
+            YIELD_RATE_CONFIG,
+            abi.encodePacked(uint64(1_500)),
+            int64(1_700_000_000)
+        );
+        bytes32 id = YieldMsgCodec.messageId(
+            PROTOCOL_CONFIG_TYPE,
+            YieldMsgCodec.PROTOCOL_VERSION,
+            7,
+            1_700_000_000,
+            payload
+        );
+        assertEq(id, ACKED_ID);
+    }
+
+    function test_depositRequest_matchesSolana() public {
+        bytes memory payload = hex"0000000000000000000000003333333333333333333333333333333333333333"
+            hex"00000000000000000000000000000000000000000000000000000000000f4240"
+            hex"4444444444444444444444444444444444444444444444444444444444444444"
+            hex"0000000000000000000000000000000000000000000000000000000000000002"
+            hex"00000000000000000000000000000000000000000000000000000000000001f4"
+            hex"0000000000000000000000000000000000000000000000000000000000000032"
+            hex"000000000000000000000000000000000000000000000000000000006553f100"
+            hex"0000000000000000000000000000000000000000000000000000000000000000";
+        {
+            (
+                bytes32 user,
+                uint64 amount,
+                bytes32 tokenMint,
+                uint64 targetProtocolId,
+                uint64 minApy,
+                uint64 slippageTolerance,
+                int64 deadline,
+                bytes32 referrer
+            ) = abi.decode(payload, (bytes32, uint64, bytes32, uint64, uint64, uint64, int64, bytes32));
+            assertEq(user, USER);
+            assertEq(amount, 1_000_000);
+            assertEq(tokenMint, TOKEN_MINT);
+            assertEq(targetProtocolId, 2);
+            assertEq(minApy, 500);
+            assertEq(slippageTolerance, 50);
+            assertEq(deadline, int64(1_700_000_000));
+            assertEq(referrer, bytes32(0));
+        }
+        assertEq(
+            abi.encode(
+                USER,
+                uint64(1_000_000),
+                TOKEN_MINT,
+                uint64(2),
+                uint64(500),
+                uint64(50),
+                int64(1_700_000_000),
+                bytes32(0)
+            ),
+            payload
+        );
+    }
+
+    function test_withdrawRequest_matchesSolana() public {
+        bytes memory destination = hex"abababababababababababababababababababab";
+        bytes memory payload = hex"0000000000000000000000003333333333333333333333333333333333333333"
+            hex"000000000000000000000000000000000000000000000000000000000003d090"
+            hex"4444444444444444444444444444444444444444444444444444444444444444"
+            hex"0000000000000000000000000000000000000000000000000000000000000002"
+            hex"0000000000000000000000000000000000000000000000000000000000007595"
+            hex"0000000000000000000000000000000000000000000000000000000000000100"
+            hex"0000000000000000000000000000000000000000000000000000000000000001"
+            hex"000000000000000000000000000000000000000000000000000000006553f100"
+            hex"0000000000000000000000000000000000000000000000000000000000000014"
+            hex"abababababababababababababababababababab000000000000000000000000";
+        {
+            (
+                bytes32 user,
+                uint64 amount,
+                bytes32 tokenMint,
+                uint64 protocolId,
+                uint32 targetChainId,
+                bytes memory destinationAddress,
+                bool withdrawYield,
+                int64 deadline
+            ) = abi.decode(payload, (bytes32, uint64, bytes32, uint64, uint32, bytes, bool, int64));
+            assertEq(user, USER);
+            assertEq(amount, 250_000);
+            assertEq(tokenMint, TOKEN_MINT);
+            assertEq(protocolId, 2);
+            assertEq(targetChainId, 30101);
+            assertEq(destinationAddress, destination);
+            assertTrue(withdrawYield);
+            assertEq(deadline, int64(1_700_000_000));
+        }
+        assertEq(
+            abi.encode(
+                USER,
+                uint64(250_000),
+                TOKEN_MINT,
+                uint64(2),
+                uint32(30101),
+                destination,
+                true,
+                int64(1_700_000_000)
+            ),
+            payload
+        );
+    }
+
+    function test_messageAck_matchesSolana() public {
+        bytes memory payload = hex"0000000000000000000000000000000000000000000000000000000000000007"
+            hex"2a0772f1e648cc32eff4373bad767edb7648f3b7a8c12121c574d910a1832eb6"
+            hex"0000000000000000000000000000000000000000000000000000000000000001"
+            hex"000000000000000000000000000000000000000000000000000000006553f100";
+        (uint64 nonce, bytes32 messageId, bool success, int64 timestamp) = abi.decode(
+            payload,
+            (uint64, bytes32, bool, int64)
+        );
+        assertEq(nonce, 7);
+        assertEq(messageId, ACKED_ID);
+        assertTrue(success);
+        assertEq(timestamp, int64(1_700_000_000));
+        assertEq(abi.encode(uint64(7), ACKED_ID, true, int64(1_700_000_000)), payload);
+    }
+
+    function test_priceUpdate_matchesSolana() public {
+        bytes memory payload = hex"1111111111111111111111111111111111111111111111111111111111111111"
+            hex"000000000000000000000000000000000000000000000000000000000280de80"
+            hex"0000000000000000000000000000000000000000000000000000000000000064"
+            hex"000000000000000000000000000000000000000000000000000000006553f100"
+            hex"00000000000000000000000000000000000000000000000000000000000000a0"
+            hex"0000000000000000000000000000000000000000000000000000000000000004"
+            hex"7079746800000000000000000000000000000000000000000000000000000000";
+        bytes32 tokenMint = 0x1111111111111111111111111111111111111111111111111111111111111111;
+        {
+            (
+                bytes32 mint,
+                uint64 price,
+                uint64 confidence,
+                int64 timestamp,
+                string memory oracleSource
+            ) = abi.decode(payload, (bytes32, uint64, uint64, int64, string));
+            assertEq(mint, tokenMint);
+            assertEq(price, 42_000_000);
+            assertEq(confidence, 100);
+            assertEq(timestamp, int64(1_700_000_000));
+            assertEq(oracleSource, "pyth");
+        }
+        assertEq(abi.encode(tokenMint, uint64(42_000_000), uint64(100), int64(1_700_000_000), string("pyth")), payload);
+    }
+
+    function test_yieldDistribution_matchesSolana() public {
+        bytes memory payload = hex"0000000000000000000000000000000000000000000000000000000000000001"
+            hex"000000000000000000000000000000000000000000000000000000000000012c"
+            hex"0000000000000000000000000000000000000000000000000000000000000032"
+            hex"00000000000000000000000000000000000000000000000000000000000000c0"
+            hex"0000000000000000000000000000000000000000000000000000000000000120"
+            hex"000000000000000000000000000000000000000000000000000000006553f100"
+            hex"0000000000000000000000000000000000000000000000000000000000000002"
+            hex"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+            hex"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
+            hex"0000000000000000000000000000000000000000000000000000000000000002"
+            hex"0000000000000000000000000000000000000000000000000000000000000064"
+            hex"00000000000000000000000000000000000000000000000000000000000000c8";
+        bytes32[] memory eligibleUsers = new bytes32[](2);
+        eligibleUsers[0] = 0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa;
+        eligibleUsers[1] = 0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb;
+        uint64[] memory perUserYield = new uint64[](2);
+        perUserYield[0] = 100;
+        perUserYield[1] = 200;
+        {
+            (
+                uint64 protocolId,
+                uint64 totalYield,
+                uint64 distributionRate,
+                bytes32[] memory users,
+                uint64[] memory yields,
+                int64 distributionTimestamp
+            ) = abi.decode(payload, (uint64, uint64, uint64, bytes32[], uint64[], int64));
+            assertEq(protocolId, 1);
+            assertEq(totalYield, 300);
+            assertEq(distributionRate, 50);
+            assertEq(keccak256(abi.encode(users)), keccak256(abi.encode(eligibleUsers)));
+            assertEq(keccak256(abi.encode(yields)), keccak256(abi.encode(perUserYield)));
+            assertEq(distributionTimestamp, int64(1_700_000_000));
+        }
+        assertEq(
+            abi.encode(uint64(1), uint64(300), uint64(50), eligibleUsers, perUserYield, int64(1_700_000_000)),
+            payload
+        );
+    }
+
+    function test_emergencyAction_matchesSolana() public {
+        bytes memory payload = hex"0000000000000000000000000000000000000000000000000000000000000003"
+            hex"0000000000000000000000000000000000000000000000000000000000000001"
+            hex"0000000000000000000000000000000000000000000000000000000000000007"
+            hex"00000000000000000000000000000000000000000000000000000000000000c0"
+            hex"2222222222222222222222222222222222222222222222222222222222222222"
+            hex"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+            hex"0000000000000000000000000000000000000000000000000000000000000007"
+            hex"6578706c6f697400000000000000000000000000000000000000000000000000";
+        bytes32 initiatedBy = 0x2222222222222222222222222222222222222222222222222222222222222222;
+        {
+            (
+                uint8 actionType,
+                bool hasProtocolId,
+                uint64 protocolId,
+                string memory reason,
+                bytes32 initiator,
+                int64 timestamp
+            ) = abi.decode(payload, (uint8, bool, uint64, string, bytes32, int64));
+            assertEq(actionType, HALT_DEPOSITS);
+            assertTrue(hasProtocolId);
+            assertEq(protocolId, 7);
+            assertEq(reason, "exploit");
+            assertEq(initiator, initiatedBy);
+            assertEq(timestamp, int64(-1));
+        }
+        assertEq(abi.encode(HALT_DEPOSITS, true, uint64(7), string("exploit"), initiatedBy, int64(-1)), payload);
+    }
+}